@@ -0,0 +1,139 @@
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion, Throughput};
+use namumark_parser::{parse, span_list, Parser};
+
+/// 문서 하나의 대략적인 크기
+const DOCUMENT_SIZE: usize = 1 << 20;
+
+/// 각주와 링크, 강조가 섞인 본문 한 줄
+fn sentence(index: usize) -> String {
+  format!(
+    "'''나무위키'''는 [[위키]] 문서 {}번이다.[* [[출처 {}|출처]]에서 가져온 ''설명''.] \
+     {{{{{{+1 큰 글씨}}}}}} ~~취소선~~과 [br] __밑줄__을 포함한다.[* 두 번째 각주]",
+    index, index
+  )
+}
+
+fn repeat_until(size: usize, mut section: impl FnMut(usize) -> String) -> String {
+  let mut document = String::with_capacity(size + 1024);
+  let mut index = 0;
+  while document.len() < size {
+    document.push_str(&section(index));
+    index += 1;
+  }
+
+  document
+}
+
+/// 문단마다 각주가 많은 일반적인 본문
+fn footnotes(size: usize) -> String {
+  repeat_until(size, |index| {
+    let mut section = format!("== 문단 {} ==\n", index);
+    for line in 0..8 {
+      section.push_str(&sentence(index * 8 + line));
+      section.push('\n');
+    }
+    section.push('\n');
+    section
+  })
+}
+
+/// 들여쓰기가 깊어지는 목록
+fn deep_list(size: usize) -> String {
+  repeat_until(size, |index| {
+    let mut section = String::new();
+    for depth in 1..=8 {
+      section.push_str(&" ".repeat(depth));
+      section.push_str(if depth % 2 == 0 { "1. " } else { "* " });
+      section.push_str(&sentence(index));
+      section.push('\n');
+    }
+    section
+  })
+}
+
+/// 셀이 많은 표
+fn large_table(size: usize) -> String {
+  repeat_until(size, |index| {
+    let mut row = String::from("||");
+    for column in 0..12 {
+      row.push_str(&format!(
+        " [[항목 {}-{}]] '''{}''' ||",
+        index, column, column
+      ));
+    }
+    row.push('\n');
+    row
+  })
+}
+
+/// 접기 문법 안에 접기 문법이 들어 있는 문단
+fn nested_folding(size: usize) -> String {
+  repeat_until(size, |index| {
+    let mut section = String::new();
+    for depth in 0..4 {
+      section.push_str(&format!("{{{{{{#!folding [ 펼치기 {} ]\n", depth));
+      section.push_str(&sentence(index));
+      section.push('\n');
+    }
+    section.push_str(&"}}}\n".repeat(4));
+    section
+  })
+}
+
+fn document_list() -> Vec<(&'static str, String)> {
+  vec![
+    ("footnotes", footnotes(DOCUMENT_SIZE)),
+    ("deep_list", deep_list(DOCUMENT_SIZE)),
+    ("large_table", large_table(DOCUMENT_SIZE)),
+    ("nested_folding", nested_folding(DOCUMENT_SIZE)),
+  ]
+}
+
+fn parse_document(c: &mut Criterion) {
+  let mut group = c.benchmark_group("parse");
+  for (name, document) in document_list() {
+    group.throughput(Throughput::BytesDecimal(document.len() as u64));
+    group.bench_with_input(
+      BenchmarkId::from_parameter(name),
+      &document,
+      |b, document| b.iter(|| parse(document)),
+    );
+  }
+  group.finish();
+}
+
+/// 블록마다 만든 트리를 사건으로 풀어 한 번 훑는 속도. 트리를 만든 뒤 푸는 것이므로 `parse`보다 할당이 적지 않다
+fn events_of_document(c: &mut Criterion) {
+  let mut group = c.benchmark_group("events");
+  for (name, document) in document_list() {
+    group.throughput(Throughput::BytesDecimal(document.len() as u64));
+    group.bench_with_input(
+      BenchmarkId::from_parameter(name),
+      &document,
+      |b, document| b.iter(|| Parser::new(document).count()),
+    );
+  }
+  group.finish();
+}
+
+/// 블록 구분 없이 인라인 문법만 읽는 속도
+fn span_list_of_document(c: &mut Criterion) {
+  let mut group = c.benchmark_group("span_list");
+  for (name, document) in document_list() {
+    group.throughput(Throughput::BytesDecimal(document.len() as u64));
+    group.bench_with_input(
+      BenchmarkId::from_parameter(name),
+      &document,
+      |b, document| b.iter(|| span_list(document)),
+    );
+  }
+  group.finish();
+}
+
+criterion_group!(
+  benches,
+  parse_document,
+  events_of_document,
+  span_list_of_document
+);
+criterion_main!(benches);
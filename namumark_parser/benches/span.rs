@@ -0,0 +1,93 @@
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion, Throughput};
+use namumark_parser::{parse, span_list};
+
+const SIZE_LIST: [usize; 3] = [1 << 16, 1 << 18, 1 << 20];
+
+/// 닫히지 않는 표시로만 이루어진 한 줄. 크기가 4배가 될 때 시간도 4배 정도여야 한다
+fn unclosed(c: &mut Criterion) {
+  let case_list: [(&str, &str); 4] = [
+    ("bracket", "["),
+    ("triple_brace", "{{{"),
+    ("strong", "'''"),
+    ("mixed", "[[{{{''~~--__^^,,["),
+  ];
+  let mut group = c.benchmark_group("unclosed");
+  for (name, pattern) in case_list.iter() {
+    for size in SIZE_LIST.iter() {
+      let source = pattern.repeat(size / pattern.len());
+      group.throughput(Throughput::Bytes(source.len() as u64));
+      group.bench_with_input(BenchmarkId::new(*name, size), &source, |b, source| {
+        b.iter(|| span_list(source))
+      });
+    }
+  }
+  group.finish();
+}
+
+/// 표시가 드문 긴 문단. 표시가 아닌 글자를 건너뛰는 속도가 드러난다
+fn paragraph(c: &mut Criterion) {
+  let sentence = "나무위키는 누구나 기여할 수 있는 위키입니다. 이 문장에는 '''강조'''와 [[링크]]가 있고, 나머지는 평범한 글자입니다. Plain ASCII text follows the Korean sentence without any markup at all.\n";
+  let mut group = c.benchmark_group("paragraph");
+  for size in SIZE_LIST.iter() {
+    let source = sentence.repeat(size / sentence.len());
+    group.throughput(Throughput::Bytes(source.len() as u64));
+    group.bench_with_input(BenchmarkId::new("parse", size), &source, |b, source| {
+      b.iter(|| parse(source))
+    });
+    group.bench_with_input(BenchmarkId::new("span_list", size), &source, |b, source| {
+      b.iter(|| span_list(source))
+    });
+  }
+  group.finish();
+}
+
+/// 표시가 하나도 없는 문단. 인라인 스캐너가 글자를 건너뛰는 속도만 남는다
+fn plain(c: &mut Criterion) {
+  let case_list: [(&str, &str); 2] = [
+    (
+      "korean",
+      "나무위키는 누구나 기여할 수 있는 위키입니다 이 문장에는 아무 문법도 없습니다 ",
+    ),
+    (
+      "ascii",
+      "Plain ASCII text without any markup at all so every byte is ordinary text ",
+    ),
+  ];
+  let size = SIZE_LIST[SIZE_LIST.len() - 1];
+  let mut group = c.benchmark_group("plain");
+  for (name, sentence) in case_list.iter() {
+    let source = sentence.repeat(size / sentence.len());
+    group.throughput(Throughput::Bytes(source.len() as u64));
+    group.bench_with_input(BenchmarkId::new("parse", name), &source, |b, source| {
+      b.iter(|| parse(source))
+    });
+    group.bench_with_input(BenchmarkId::new("span_list", name), &source, |b, source| {
+      b.iter(|| span_list(source))
+    });
+  }
+  group.finish();
+}
+
+/// 깊이 제한에 걸리는 중첩
+fn nested(c: &mut Criterion) {
+  let mut group = c.benchmark_group("nested");
+  for size in SIZE_LIST.iter() {
+    let blockquote = ">".repeat(*size);
+    group.throughput(Throughput::Bytes(blockquote.len() as u64));
+    group.bench_with_input(
+      BenchmarkId::new("blockquote", size),
+      &blockquote,
+      |b, source| b.iter(|| parse(source)),
+    );
+
+    let folding = "{{{#!folding a\n".repeat(size / 16);
+    group.throughput(Throughput::Bytes(folding.len() as u64));
+    group.bench_with_input(BenchmarkId::new("folding", size), &folding, |b, source| {
+      b.iter(|| parse(source))
+    });
+  }
+  group.finish();
+}
+
+criterion_group!(benches, unclosed, paragraph, plain, nested);
+criterion_main!(benches);
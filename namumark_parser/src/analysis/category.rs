@@ -0,0 +1,66 @@
+use crate::{Block, Category, CommandSpan, PageTitle, Visitor};
+use std::collections::HashSet;
+
+/// 문서에 나온 순서대로 분류를 모은다. 같은 분류가 여러 번 나오면 처음 것만 남긴다
+pub fn categories<'a>(block_list: &'a [Block]) -> Vec<Category<'a>> {
+  let mut collector = CategoryCollector {
+    category_list: vec![],
+    title_set: HashSet::new(),
+  };
+  collector.visit_block_list(block_list);
+
+  collector.category_list
+}
+
+struct CategoryCollector<'a> {
+  category_list: Vec<Category<'a>>,
+  /// 이미 모은 분류의 제목
+  title_set: HashSet<&'a PageTitle<'a>>,
+}
+
+impl<'a> Visitor<'a> for CategoryCollector<'a> {
+  fn visit_command_span(&mut self, span: &'a CommandSpan<'a>) {
+    if let CommandSpan::Category(category) = span {
+      if self.title_set.insert(&category.title) {
+        self.category_list.push(category.clone());
+      }
+    }
+    self.walk_command_span(span);
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use crate::*;
+
+  #[test]
+  fn ordered_and_deduplicated() {
+    let source = "== [[분류:b]] ==\n[[분류:a#blur|키]] * \n * [[분류:b]] {{{#!folding [[Category:c]]}}}\n>'''[[분류:a]]'''";
+    let block_list = parse(source);
+    assert_eq!(
+      categories(&block_list),
+      vec![
+        Category {
+          title: PageTitle::new(Some("분류"), "b"),
+          sort_key: None,
+          blur: false
+        },
+        Category {
+          title: PageTitle::new(Some("분류"), "a"),
+          sort_key: Some("키".into()),
+          blur: true
+        },
+        Category {
+          title: PageTitle::new(Some("분류"), "c"),
+          sort_key: None,
+          blur: false
+        }
+      ]
+    );
+  }
+
+  #[test]
+  fn empty() {
+    assert_eq!(categories(&parse("[[foo]]")), vec![]);
+  }
+}
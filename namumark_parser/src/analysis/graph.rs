@@ -0,0 +1,436 @@
+use crate::{Block, CommandSpan, Document, MacroSpan, NamespaceRegistry, Visitor};
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, BTreeSet, HashSet};
+
+const PARENT_LINK: &str = "../";
+const CHILD_SEPARATOR: char = '/';
+const EXTERNAL_LINK: &str = "://";
+
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum EdgeKind {
+  /// `[[문서]]`
+  Link,
+  /// `[[파일:a.png]]`
+  File,
+  /// `[[분류:이름]]`
+  Category,
+  /// `[include(틀:이름)]`
+  Transclusion,
+}
+
+/// 문서에서 다른 문서로 향하는 연결. 대상은 정식 이름공간을 사용한 제목이다
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Edge {
+  pub target: String,
+  pub kind: EdgeKind,
+}
+
+/// 여러 문서의 연결과 역링크. 문서를 다시 넣으면 이전 연결을 지우고 새로 계산한다
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct LinkGraph {
+  edge_map: BTreeMap<String, Vec<Edge>>,
+  redirect_map: BTreeMap<String, String>,
+  backlink_map: BTreeMap<String, BTreeSet<(String, EdgeKind)>>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Backlink<'g> {
+  pub source: &'g str,
+  pub kind: EdgeKind,
+}
+
+/// 넘겨주기를 따라간 경로. 첫 원소는 출발한 문서다
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RedirectChain<'g> {
+  pub path: Vec<&'g str>,
+  pub end: RedirectEnd,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RedirectEnd {
+  /// 넘겨주기가 아닌 문서에 도착했다
+  Document,
+  /// 없는 문서를 가리킨다
+  Missing,
+  /// 이미 지나온 문서로 돌아온다
+  Cycle,
+}
+
+/// 문서에 나온 순서대로 연결을 모은다. 같은 대상과 종류는 한 번만 남기고, 자기 자신과 외부 링크는 뺀다
+pub fn edges(title: &str, block_list: &[Block], namespaces: &NamespaceRegistry) -> Vec<Edge> {
+  let mut collector = EdgeCollector {
+    title: namespaces.title(title).to_string(),
+    namespaces,
+    edge_list: vec![],
+    edge_set: HashSet::new(),
+  };
+  collector.visit_block_list(block_list);
+
+  collector.edge_list
+}
+
+struct EdgeCollector<'r> {
+  title: String,
+  namespaces: &'r NamespaceRegistry,
+  edge_list: Vec<Edge>,
+  /// 이미 모은 연결
+  edge_set: HashSet<Edge>,
+}
+
+impl<'r> EdgeCollector<'r> {
+  fn push(&mut self, target: String, kind: EdgeKind) {
+    let edge = Edge { target, kind };
+    if edge.target != self.title && self.edge_set.insert(edge.clone()) {
+      self.edge_list.push(edge);
+    }
+  }
+}
+
+impl<'a, 'r> Visitor<'a> for EdgeCollector<'r> {
+  fn visit_command_span(&mut self, span: &'a CommandSpan<'a>) {
+    match span {
+      CommandSpan::Link(_, link, _) => {
+        if let Some(target) = link_target(&self.title, link) {
+          let target = self.namespaces.title(&target).to_string();
+          self.push(target, EdgeKind::Link);
+        }
+      }
+      CommandSpan::Image(target, _) => self.push(target.to_string(), EdgeKind::File),
+      CommandSpan::Category(category) => self.push(category.title.to_string(), EdgeKind::Category),
+      CommandSpan::Video(_, _) => {}
+    }
+    self.walk_command_span(span);
+  }
+
+  fn visit_macro_span(&mut self, span: &'a MacroSpan<'a>) {
+    if let MacroSpan::Include(target, _) = span {
+      self.push(target.to_string(), EdgeKind::Transclusion);
+    }
+    self.walk_macro_span(span);
+  }
+}
+
+/// `../`과 `/하위 문서`는 `source`를 기준으로 바꾸고, 앞의 `:`은 버린다. 문단 이름은 파서가 이미 떼어 냈다
+fn link_target(source: &str, link: &str) -> Option<String> {
+  let link = link.trim();
+  let link = link.strip_prefix(':').unwrap_or(link);
+  if link.contains(EXTERNAL_LINK) {
+    return None;
+  }
+  let link = link.trim_end();
+  if link.is_empty() {
+    None
+  } else if link == PARENT_LINK {
+    source
+      .rfind(CHILD_SEPARATOR)
+      .map(|index| source[..index].to_owned())
+  } else if link.starts_with(CHILD_SEPARATOR) {
+    Some(format!("{}{}", source, link))
+  } else {
+    Some(link.to_owned())
+  }
+}
+
+impl LinkGraph {
+  pub fn new() -> Self {
+    Default::default()
+  }
+
+  /// 같은 제목의 문서가 있었다면 바꾼다
+  pub fn insert(&mut self, title: &str, document: &Document, namespaces: &NamespaceRegistry) {
+    let title = namespaces.title(title).to_string();
+    self.remove(&title);
+    let edge_list = match document {
+      Document::Redirect(redirect) => {
+        self
+          .redirect_map
+          .insert(title.clone(), redirect.title.to_string());
+        vec![]
+      }
+      Document::Content(block_list) => edges(&title, block_list, namespaces),
+    };
+    for edge in &edge_list {
+      self
+        .backlink_map
+        .entry(edge.target.clone())
+        .or_default()
+        .insert((title.clone(), edge.kind));
+    }
+    self.edge_map.insert(title, edge_list);
+  }
+
+  pub fn remove(&mut self, title: &str) -> bool {
+    let edge_list = match self.edge_map.remove(title) {
+      Some(edge_list) => edge_list,
+      None => return false,
+    };
+    self.redirect_map.remove(title);
+    for edge in edge_list {
+      if let Some(source_set) = self.backlink_map.get_mut(&edge.target) {
+        source_set.remove(&(title.to_owned(), edge.kind));
+        if source_set.is_empty() {
+          self.backlink_map.remove(&edge.target);
+        }
+      }
+    }
+
+    true
+  }
+
+  pub fn contains(&self, title: &str) -> bool {
+    self.edge_map.contains_key(title)
+  }
+
+  pub fn titles(&self) -> impl Iterator<Item = &str> {
+    self.edge_map.keys().map(String::as_str)
+  }
+
+  pub fn outgoing(&self, title: &str) -> &[Edge] {
+    self.edge_map.get(title).map_or(&[], Vec::as_slice)
+  }
+
+  /// 역링크. 넘겨주기는 `redirects_to`로 따로 찾는다
+  pub fn backlinks(&self, title: &str) -> Vec<Backlink<'_>> {
+    self
+      .backlink_map
+      .get(title)
+      .into_iter()
+      .flatten()
+      .map(|(source, kind)| Backlink {
+        source,
+        kind: *kind,
+      })
+      .collect()
+  }
+
+  pub fn redirects_to(&self, title: &str) -> Vec<&str> {
+    self
+      .redirect_map
+      .iter()
+      .filter(|(_, target)| *target == title)
+      .map(|(source, _)| source.as_str())
+      .collect()
+  }
+
+  /// 다른 문서에서 연결하거나 넘겨주지 않는 문서. 넘겨주기 문서는 제외한다
+  pub fn orphans(&self) -> Vec<&str> {
+    let redirect_target_set: BTreeSet<&str> =
+      self.redirect_map.values().map(String::as_str).collect();
+
+    self
+      .titles()
+      .filter(|title| !self.redirect_map.contains_key(*title))
+      .filter(|title| !self.backlink_map.contains_key(*title))
+      .filter(|title| !redirect_target_set.contains(title))
+      .collect()
+  }
+
+  /// 연결은 있지만 아직 없는 문서와, 그 문서를 가리키는 문서 수. 많이 가리키는 순서로 나열한다
+  pub fn wanted(&self) -> Vec<(&str, usize)> {
+    let mut source_map: BTreeMap<&str, BTreeSet<&str>> = BTreeMap::new();
+    for (target, source_set) in &self.backlink_map {
+      source_map
+        .entry(target)
+        .or_default()
+        .extend(source_set.iter().map(|(source, _)| source.as_str()));
+    }
+    for (source, target) in &self.redirect_map {
+      source_map.entry(target).or_default().insert(source);
+    }
+    let mut wanted_list: Vec<(&str, usize)> = source_map
+      .into_iter()
+      .filter(|(target, _)| !self.contains(target))
+      .map(|(target, source_set)| (target, source_set.len()))
+      .collect();
+    wanted_list.sort_by(|(a, a_count), (b, b_count)| b_count.cmp(a_count).then(a.cmp(b)));
+
+    wanted_list
+  }
+
+  pub fn redirect_chain<'g>(&'g self, title: &'g str) -> RedirectChain<'g> {
+    let mut path = vec![title];
+    let mut current = title;
+    loop {
+      let end = match self.redirect_map.get(current) {
+        Some(target) if path.contains(&target.as_str()) => RedirectEnd::Cycle,
+        Some(target) => {
+          current = target;
+          path.push(current);
+          continue;
+        }
+        None if self.contains(current) => RedirectEnd::Document,
+        None => RedirectEnd::Missing,
+      };
+
+      return RedirectChain { path, end };
+    }
+  }
+
+  /// 넘겨주기 문서로 넘겨주거나 순환하는 넘겨주기
+  pub fn double_redirects(&self) -> Vec<RedirectChain<'_>> {
+    self
+      .redirect_map
+      .keys()
+      .map(|title| self.redirect_chain(title))
+      .filter(|chain| chain.path.len() > 2 || chain.end == RedirectEnd::Cycle)
+      .collect()
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use crate::*;
+
+  fn graph(document_list: &[(&str, &str)]) -> LinkGraph {
+    let namespaces = NamespaceRegistry::default();
+    let mut graph = LinkGraph::new();
+    for (title, source) in document_list {
+      graph.insert(title, &parse_document(source), &namespaces);
+    }
+
+    graph
+  }
+
+  fn edge(target: &str, kind: EdgeKind) -> Edge {
+    Edge {
+      target: target.to_owned(),
+      kind,
+    }
+  }
+
+  #[test]
+  fn edges() {
+    let source = "[[b#s-1]] [[b]] [[https://example.com]] [[#s-2]] [[a]] [[/하위]] [[../]]\n\
+                  [[파일:c.png]] [[Category:d]] [include(Template:e, x=1)] [[:분류:d]]";
+    assert_eq!(
+      crate::edges("a/b", &parse(source), &Default::default()),
+      vec![
+        edge("b", EdgeKind::Link),
+        edge("a", EdgeKind::Link),
+        edge("a/b/하위", EdgeKind::Link),
+        edge("파일:c.png", EdgeKind::File),
+        edge("분류:d", EdgeKind::Category),
+        edge("틀:e", EdgeKind::Transclusion),
+        edge("분류:d", EdgeKind::Link),
+      ]
+    );
+  }
+
+  #[test]
+  fn escaped_anchor() {
+    assert_eq!(
+      crate::edges(
+        "a",
+        &parse("[[C\\#]] [[C\\##s-1|C#]] [[C]]"),
+        &Default::default()
+      ),
+      vec![edge("C#", EdgeKind::Link), edge("C", EdgeKind::Link)]
+    );
+  }
+
+  #[test]
+  fn backlinks() {
+    let graph = graph(&[
+      ("a", "[[b]] [[c]]"),
+      ("b", "[[c]] [include(틀:c)]"),
+      ("c", ""),
+    ]);
+    assert_eq!(
+      graph.backlinks("c"),
+      vec![
+        Backlink {
+          source: "a",
+          kind: EdgeKind::Link
+        },
+        Backlink {
+          source: "b",
+          kind: EdgeKind::Link
+        },
+      ]
+    );
+    assert_eq!(
+      graph.backlinks("틀:c"),
+      vec![Backlink {
+        source: "b",
+        kind: EdgeKind::Transclusion
+      }]
+    );
+  }
+
+  #[test]
+  fn reinsert() {
+    let namespaces = NamespaceRegistry::default();
+    let mut graph = graph(&[("a", "[[b]]"), ("b", "")]);
+    graph.insert("a", &parse_document("[[c]]"), &namespaces);
+    assert_eq!(graph.backlinks("b"), vec![]);
+    assert_eq!(graph.outgoing("a"), &[edge("c", EdgeKind::Link)]);
+    assert!(graph.remove("a"));
+    assert!(!graph.remove("a"));
+    assert_eq!(graph.backlinks("c"), vec![]);
+  }
+
+  #[test]
+  fn orphans_and_wanted() {
+    let graph = graph(&[
+      ("a", "[[b]] [[x]] [[분류:y]]"),
+      ("b", "[[x]]"),
+      ("c", ""),
+      ("d", "#redirect e"),
+      ("e", ""),
+    ]);
+    assert_eq!(graph.orphans(), vec!["a", "c"]);
+    assert_eq!(graph.wanted(), vec![("x", 2), ("분류:y", 1)]);
+    assert_eq!(graph.redirects_to("e"), vec!["d"]);
+  }
+
+  #[test]
+  fn redirect_chain() {
+    let graph = graph(&[
+      ("a", "#redirect b"),
+      ("b", "#redirect c"),
+      ("c", "본문"),
+      ("d", "#redirect 없는 문서"),
+      ("e", "#redirect f"),
+      ("f", "#redirect e"),
+    ]);
+    assert_eq!(
+      graph.redirect_chain("a"),
+      RedirectChain {
+        path: vec!["a", "b", "c"],
+        end: RedirectEnd::Document
+      }
+    );
+    assert_eq!(
+      graph.redirect_chain("d"),
+      RedirectChain {
+        path: vec!["d", "없는 문서"],
+        end: RedirectEnd::Missing
+      }
+    );
+    assert_eq!(
+      graph
+        .double_redirects()
+        .into_iter()
+        .map(|chain| (chain.path[0], chain.end))
+        .collect::<Vec<_>>(),
+      vec![
+        ("a", RedirectEnd::Document),
+        ("e", RedirectEnd::Cycle),
+        ("f", RedirectEnd::Cycle)
+      ]
+    );
+    assert_eq!(graph.wanted(), vec![("없는 문서", 1)]);
+  }
+
+  #[cfg(feature = "dump")]
+  #[test]
+  fn serialize() {
+    let graph = graph(&[("a", "[[b]] [[파일:c.png]]"), ("b", "#redirect a")]);
+    let json = serde_json::to_string(&graph).unwrap();
+    assert_eq!(serde_json::from_str::<LinkGraph>(&json).unwrap(), graph);
+  }
+}
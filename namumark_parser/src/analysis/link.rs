@@ -0,0 +1,43 @@
+use crate::{Block, CommandSpan, Visitor};
+use std::collections::HashSet;
+
+/// 문서에 나온 순서대로 링크 대상을 모은다. 같은 대상이 여러 번 나오면 처음 것만 남긴다.
+/// `[[:분류:foo]]`처럼 앞에 붙은 `:`과 `#` 뒤의 문단 이름은 떼어 낸다
+pub fn links<'a>(block_list: &'a [Block]) -> Vec<&'a str> {
+  let mut collector = LinkCollector {
+    link_list: vec![],
+    link_set: HashSet::new(),
+  };
+  collector.visit_block_list(block_list);
+
+  collector.link_list
+}
+
+struct LinkCollector<'a> {
+  link_list: Vec<&'a str>,
+  /// 이미 모은 링크 대상
+  link_set: HashSet<&'a str>,
+}
+
+impl<'a> Visitor<'a> for LinkCollector<'a> {
+  fn visit_command_span(&mut self, span: &'a CommandSpan<'a>) {
+    if let CommandSpan::Link(_, link, _) = span {
+      let link = link.strip_prefix(':').unwrap_or(link);
+      if self.link_set.insert(link) {
+        self.link_list.push(link);
+      }
+    }
+    self.walk_command_span(span);
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use crate::*;
+
+  #[test]
+  fn ordered_and_deduplicated() {
+    let source = "[[b#s-1]] [[a|에이]]\n * [[b]] {{{#!folding [[c]]}}}\n[[분류:d]] [[:분류:d]]";
+    assert_eq!(links(&parse(source)), vec!["b", "a", "c", "분류:d"]);
+  }
+}
@@ -0,0 +1,7 @@
+mod category;
+mod graph;
+mod link;
+
+pub use category::categories;
+pub use graph::{edges, Backlink, Edge, EdgeKind, LinkGraph, RedirectChain, RedirectEnd};
+pub use link::links;
@@ -1,7 +1,7 @@
 mod multiline_block;
 mod singleline_block;
 
-use crate::Result;
+use crate::{Context, ParseOptions, Result};
 use multiline_block::multiline_block;
 pub(crate) use multiline_block::multiline_block_list;
 pub use multiline_block::{ListIndex, ListItem, MultilineBlock};
@@ -14,10 +14,18 @@ pub enum Block<'a> {
   Multiline(MultilineBlock<'a>),
 }
 
-pub fn block_list(mut input: &str) -> Vec<Block> {
+pub fn parse(input: &str) -> Vec<Block> {
+  parse_with(input, &Default::default())
+}
+
+pub fn parse_with<'a>(input: &'a str, options: &ParseOptions) -> Vec<Block<'a>> {
+  block_list(input, Context::new(options))
+}
+
+pub(crate) fn block_list<'a>(mut input: &'a str, context: Context) -> Vec<Block<'a>> {
   let mut block_list = vec![];
 
-  while let Ok((next_input, block)) = block(input) {
+  while let Ok((next_input, block)) = block(input, context) {
     block_list.push(block);
     if next_input.is_empty() {
       break;
@@ -28,11 +36,11 @@ pub fn block_list(mut input: &str) -> Vec<Block> {
   block_list
 }
 
-fn block(input: &str) -> Result<Block> {
-  if let Ok((input, block)) = singleline_block(input) {
+pub(crate) fn block<'a>(input: &'a str, context: Context) -> Result<'a, Block<'a>> {
+  if let Ok((input, block)) = singleline_block(input, context) {
     Ok((input, Block::Singleline(block)))
   } else {
-    let (input, block) = multiline_block(input)?;
+    let (input, block) = multiline_block(input, context)?;
 
     Ok((input, Block::Multiline(block)))
   }
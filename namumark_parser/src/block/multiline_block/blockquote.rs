@@ -1,28 +1,71 @@
-use super::{indent, list, paragraph};
-use crate::{linebreak, whitespace, MultilineBlock, Result};
-use nom::{branch::alt, character::complete::char};
+use super::list::starts_with_list;
+use crate::{
+  line, multiline_block_list,
+  rebase::{Rebase, Reborrow},
+  whitespace, Context, MultilineBlock, Result, Syntax,
+};
+use nom::{character::complete::char, error::ErrorKind, Err};
+use std::borrow::Cow;
 
-pub(crate) fn blockquote(input: &str) -> Result<MultilineBlock> {
+/// `>`를 벗겨 낸 줄이 인용 안쪽의 문자열과 입력에서 각각 어디에 있는지
+struct QuotedLine {
+  quoted_start: usize,
+  input_start: usize,
+  len: usize,
+}
+
+/// 인용 안쪽의 문자열을 가리키는 문자열을 입력의 같은 내용으로 옮긴다.
+/// 여러 줄에 걸친 문자열은 입력에서 이어져 있지 않으므로 복사한다
+struct Unquote<'q, 'a> {
+  quoted: &'q str,
+  input: &'a str,
+  line_list: Vec<QuotedLine>,
+}
+
+pub(crate) fn blockquote<'a>(input: &'a str, context: Context) -> Result<'a, MultilineBlock<'a>> {
+  let _ = context.expect(input, Syntax::Blockquote)?;
   let _ = expect_blockquote(input)?;
+  // `>`가 겹칠 때마다 다시 호출되므로 깊이를 제한한다. 한 단계 들어가는 것은 `multiline_block_list`가 한다
+  let _ = context
+    .nested()
+    .ok_or(Err::Error((input, ErrorKind::TooLarge)))?;
 
-  let mut index = 0;
-  let mut block_list = vec![];
-
-  for line in input
-    .lines()
-    .take_while(|&line| starts_with_blockquote(line))
-  {
-    index += line.len() + 1;
-    let line = whitespace(&line[1..]);
-    if let Ok((_, block)) = alt((list, indent, blockquote, paragraph))(line) {
-      block_list.push(block);
+  // 인용된 줄을 모두 모아 `>`를 하나씩 벗기고 블록으로 다시 파싱한다
+  let mut rest = input;
+  let mut quoted = String::new();
+  let mut line_list = vec![];
+  while starts_with_blockquote(rest) {
+    let (next_input, quoted_line) = line(rest);
+    let quoted_line = quoted_line.strip_suffix('\r').unwrap_or(quoted_line);
+    // `> * 항목`처럼 목록을 쓸 때는 표시 앞의 공백을 남긴다
+    let quoted_line = match &quoted_line[1..] {
+      quoted_line if starts_with_list(quoted_line) => quoted_line,
+      quoted_line => whitespace(quoted_line),
+    };
+    if !line_list.is_empty() {
+      quoted.push('\n');
     }
+    line_list.push(QuotedLine {
+      quoted_start: quoted.len(),
+      input_start: quoted_line.as_ptr() as usize - input.as_ptr() as usize,
+      len: quoted_line.len(),
+    });
+    quoted.push_str(quoted_line);
+    rest = next_input;
   }
 
-  let input = linebreak(&input[index - 1..]);
+  let block_list = multiline_block_list(&quoted, context);
+  let unquote = Unquote {
+    quoted: &quoted,
+    input,
+    line_list,
+  };
+  let block_list = block_list
+    .rebase(&unquote)
+    .expect("옮길 수 없는 문자열은 복사한다");
   let block = MultilineBlock::Blockquote(block_list);
 
-  Ok((input, block))
+  Ok((rest, block))
 }
 
 fn expect_blockquote(input: &str) -> Result {
@@ -35,9 +78,141 @@ pub(crate) fn starts_with_blockquote(input: &str) -> bool {
   input.starts_with('>')
 }
 
+impl<'q, 'a> Unquote<'q, 'a> {
+  fn str(&self, source: &'q str) -> Option<&'a str> {
+    let start = (source.as_ptr() as usize).checked_sub(self.quoted.as_ptr() as usize)?;
+    let end = start + source.len();
+    let index = self
+      .line_list
+      .partition_point(|line| line.quoted_start <= start)
+      .checked_sub(1)?;
+    let line = &self.line_list[index];
+    if end > line.quoted_start + line.len {
+      return None;
+    }
+    let start = line.input_start + start - line.quoted_start;
+
+    self.input.get(start..start + source.len())
+  }
+}
+
+impl<'q, 'a> Reborrow<'q, 'a> for Unquote<'q, 'a> {
+  fn cow(&self, source: Cow<'q, str>) -> Option<Cow<'a, str>> {
+    Some(match source {
+      Cow::Borrowed(source) => match self.str(source) {
+        Some(source) => Cow::Borrowed(source),
+        None => Cow::Owned(source.to_owned()),
+      },
+      Cow::Owned(source) => Cow::Owned(source),
+    })
+  }
+}
+
 #[cfg(test)]
 mod tests {
   use crate::*;
+  use std::borrow::Cow;
+
+  fn paragraph(text: &str) -> MultilineBlock {
+    MultilineBlock::Paragraph(vec![Span::Inline(text.into())])
+  }
+
+  #[test]
+  fn same_as_unquoted() {
+    for source in [
+      "a\n\nb",
+      "a\n{{{#!folding 접기\nb}}}",
+      " 1. a\n  * b\n 1.#3 c",
+    ]
+    .iter()
+    {
+      let quoted: String = source.lines().map(|line| format!(">{}\n", line)).collect();
+      let block_list = parse(source)
+        .into_iter()
+        .map(|block| match block {
+          Block::Multiline(block) => block,
+          block => panic!("unexpected {:?}", block),
+        })
+        .collect();
+      assert_eq!(
+        parse(&quoted),
+        vec![Block::Multiline(MultilineBlock::Blockquote(block_list))],
+        "{:?}",
+        source
+      );
+    }
+  }
+
+  #[test]
+  fn nested() {
+    assert_eq!(
+      parse(">a\n>>b\n>>c\n>d"),
+      vec![Block::Multiline(MultilineBlock::Blockquote(vec![
+        MultilineBlock::Paragraph(vec![
+          Span::Inline("a".into()),
+          Span::Semantic(SemanticSpan::Linebreak)
+        ]),
+        MultilineBlock::Blockquote(vec![MultilineBlock::Paragraph(vec![
+          Span::Inline("b".into()),
+          Span::Semantic(SemanticSpan::Linebreak),
+          Span::Inline("c".into())
+        ])]),
+        paragraph("d")
+      ]))]
+    );
+  }
+
+  #[test]
+  fn list() {
+    assert_eq!(
+      parse("> * a\n>  * b"),
+      vec![Block::Multiline(MultilineBlock::Blockquote(vec![
+        MultilineBlock::UnorderedList(
+          vec![ListItem(vec![
+            paragraph("a"),
+            MultilineBlock::UnorderedList(vec![ListItem(vec![paragraph("b")])], 2)
+          ])],
+          1
+        )
+      ]))]
+    );
+  }
+
+  #[test]
+  fn multiline_bracket() {
+    assert_eq!(
+      parse(">{{{a\n>b}}}\nc"),
+      vec![
+        Block::Multiline(MultilineBlock::Blockquote(vec![MultilineBlock::Paragraph(
+          vec![Span::Bracket(BracketSpan::Inline("a\nb".into()))]
+        )])),
+        Block::Multiline(paragraph("c"))
+      ]
+    );
+  }
+
+  #[test]
+  fn borrowed() {
+    let source = ">a\r\n> b";
+    let block_list = parse(source);
+    let span_list = match &block_list[..] {
+      [Block::Multiline(MultilineBlock::Blockquote(block_list))] => match &block_list[..] {
+        [MultilineBlock::Paragraph(span_list)] => span_list,
+        block_list => panic!("unexpected {:?}", block_list),
+      },
+      block_list => panic!("unexpected {:?}", block_list),
+    };
+    for (span, text) in span_list.iter().step_by(2).zip(["a", "b"].iter()) {
+      match span {
+        Span::Inline(Cow::Borrowed(inline)) => {
+          assert_eq!(inline, text);
+          let offset = inline.as_ptr() as usize - source.as_ptr() as usize;
+          assert_eq!(&source[offset..offset + inline.len()], *text);
+        }
+        span => panic!("unexpected {:?}", span),
+      }
+    }
+  }
 
   #[test]
   fn a_line() {
@@ -45,7 +220,7 @@ mod tests {
     assert_eq!(
       parse(source),
       vec![Block::Multiline(MultilineBlock::Blockquote(vec![
-        MultilineBlock::Paragraph(vec![Span::Inline("Danuel")])
+        MultilineBlock::Paragraph(vec![Span::Inline("Danuel".into())])
       ]))]
     )
   }
@@ -56,7 +231,7 @@ mod tests {
     assert_eq!(
       parse(source),
       vec![Block::Multiline(MultilineBlock::Blockquote(vec![
-        MultilineBlock::Paragraph(vec![Span::Inline("Danuel")])
+        MultilineBlock::Paragraph(vec![Span::Inline("Danuel".into())])
       ]))]
     )
   }
@@ -68,7 +243,7 @@ mod tests {
       parse(source),
       vec![Block::Multiline(MultilineBlock::Blockquote(vec![
         MultilineBlock::Indent(vec![MultilineBlock::Paragraph(vec![Span::Inline(
-          "Danuel"
+          "Danuel".into()
         )])])
       ]))]
     )
@@ -82,9 +257,11 @@ Danuel";
       parse(source),
       vec![
         Block::Multiline(MultilineBlock::Blockquote(vec![MultilineBlock::Paragraph(
-          vec![Span::Inline("Danuel")]
+          vec![Span::Inline("Danuel".into())]
         )])),
-        Block::Multiline(MultilineBlock::Paragraph(vec![Span::Inline("Danuel")]))
+        Block::Multiline(MultilineBlock::Paragraph(vec![Span::Inline(
+          "Danuel".into()
+        )]))
       ]
     )
   }
@@ -96,9 +273,37 @@ Danuel";
     assert_eq!(
       parse(source),
       vec![Block::Multiline(MultilineBlock::Blockquote(vec![
-        MultilineBlock::Paragraph(vec![Span::Inline("Danuel")]),
-        MultilineBlock::Paragraph(vec![Span::Inline("Danuel")])
-      ],))]
+        MultilineBlock::Paragraph(vec![
+          Span::Inline("Danuel".into()),
+          Span::Semantic(SemanticSpan::Linebreak),
+          Span::Inline("Danuel".into())
+        ])
+      ]))]
     )
   }
+
+  #[test]
+  fn max_depth() {
+    let options = ParseOptions::new().max_depth(2);
+    assert_eq!(
+      parse_with(">>>Danuel", &options),
+      vec![Block::Multiline(MultilineBlock::Blockquote(vec![
+        MultilineBlock::Blockquote(vec![MultilineBlock::Paragraph(vec![Span::Inline(
+          ">Danuel".into()
+        )])])
+      ]))]
+    );
+    assert_eq!(
+      parse_with(">>Danuel", &ParseOptions::new().max_depth(1)),
+      vec![Block::Multiline(MultilineBlock::Blockquote(vec![
+        MultilineBlock::Paragraph(vec![Span::Inline(">Danuel".into())])
+      ]))]
+    );
+  }
+
+  #[test]
+  fn deeply_nested() {
+    let source = ">".repeat(1 << 16);
+    assert!(!parse(&source).is_empty());
+  }
 }
@@ -1,10 +1,14 @@
-use crate::{line, MultilineBlock, Result};
+use crate::{line, Context, MultilineBlock, Result, Syntax};
 use nom::{
   bytes::complete::{take_while, take_while_m_n},
   combinator::all_consuming,
 };
 
-pub(crate) fn horizontal_rule(input: &str) -> Result<MultilineBlock> {
+pub(crate) fn horizontal_rule<'a>(
+  input: &'a str,
+  context: Context,
+) -> Result<'a, MultilineBlock<'a>> {
+  let (input, _) = context.expect(input, Syntax::HorizontalRule)?;
   let (input, line) = line(input);
   let _ = all_consuming(is_valid)(line)?;
   let block = MultilineBlock::HorizontalRule;
@@ -35,7 +39,7 @@ mod tests {
     assert_eq!(
       parse(source),
       vec![Block::Multiline(MultilineBlock::Paragraph(vec![
-        Span::Inline("---")
+        Span::Inline("---".into())
       ]))]
     );
   }
@@ -100,9 +104,7 @@ mod tests {
     assert_eq!(
       parse(source),
       vec![Block::Multiline(MultilineBlock::Paragraph(vec![
-        Span::Semantic(SemanticSpan::Delete(vec![])),
-        Span::Semantic(SemanticSpan::Delete(vec![])),
-        Span::Inline("--")
+        Span::Inline("----------".into())
       ]))]
     );
   }
@@ -1,10 +1,11 @@
-use crate::{line_with_bracket, multiline_block_list, MultilineBlock, Result};
+use crate::{line_with_bracket, multiline_block_list, Context, MultilineBlock, Result, Syntax};
 use nom::character::complete::char;
 
-pub(crate) fn indent(input: &str) -> Result<MultilineBlock> {
+pub(crate) fn indent<'a>(input: &'a str, context: Context) -> Result<'a, MultilineBlock<'a>> {
+  let (input, _) = context.expect(input, Syntax::Indent)?;
   let (input, _) = expect_indent(input)?;
   let (input, line) = line_with_bracket(input);
-  let block = MultilineBlock::Indent(multiline_block_list(line));
+  let block = MultilineBlock::Indent(multiline_block_list(line, context));
 
   Ok((input, block))
 }
@@ -1,188 +1,149 @@
 use crate::{
-  line_with_bracket, linebreak, multiline_block_list, whitespace, ListIndex, ListItem,
-  MultilineBlock, Result,
+  line_with_bracket, linebreak, multiline_block_list, whitespace, Context, ListIndex, ListItem,
+  MultilineBlock, Result, SemanticSpan, Span, Syntax,
 };
 use nom::{
-  branch::alt,
-  bytes::complete::{is_a, tag},
-  character::complete::char,
+  character::complete::{char, digit1},
   combinator::opt,
+  error::ErrorKind,
+  Err,
 };
 
-const UNORDERED_LIST: &str = " *";
+/// 목록 표시. 앞의 공백은 들여쓰기로 따로 센다
+const MARKER_LIST: [&str; 8] = ["*", "1.", "a.", "A.", "i.", "I.", "ㄱ.", "가."];
+
+/// 목록 항목이 시작되는 줄
+struct ListLine<'a> {
+  /// 표시 앞의 공백 수
+  indent: usize,
+  marker: &'static str,
+  /// `#N`으로 지정한 시작 번호
+  start: Option<usize>,
+  /// 표시와 시작 번호 다음부터의 입력
+  rest: &'a str,
+}
 
-pub(crate) fn list(input: &str) -> Result<MultilineBlock> {
-  alt((unordered_list, ordered_list))(input)
+pub(crate) fn list<'a>(input: &'a str, context: Context) -> Result<'a, MultilineBlock<'a>> {
+  let (input, _) = context.expect(input, Syntax::List)?;
+  list_of_indent(input, context)
 }
 
 pub(crate) fn starts_with_list(input: &str) -> bool {
-  starts_with_unordered_list(input) || starts_with_ordered_list(input)
+  list_line(input).is_some()
 }
 
-fn unordered_list(input: &str) -> Result<MultilineBlock> {
-  let (input, _) = expect_unorder_list(input)?;
-  let (mut input, item) = list_item_line(input);
-
+/// 첫 줄과 들여쓰기와 표시가 같은 항목을 모은다.
+/// 더 깊이 들여쓴 줄은 마지막 항목에 속하는데, 목록 표시가 있으면 하위 목록이 되고 없으면 항목의 내용이 이어진다
+fn list_of_indent<'a>(input: &'a str, context: Context) -> Result<'a, MultilineBlock<'a>> {
+  let first_line = list_line(input).ok_or(Err::Error((input, ErrorKind::Verify)))?;
+  let item_context = context.in_list();
+  let mut input = input;
   let mut item_list = vec![];
-  if !item.is_empty() {
-    item_list.push(list_item(item))
-  }
 
-  while input.starts_with(' ') && starts_with_list(input) {
-    if let Ok((next_input, _)) = expect_unorder_list(input) {
-      let (next_input, cursor) = list_start(next_input)?;
-      if cursor.is_some() {
-        break;
-      }
-      let (next_input, item) = list_item_line(next_input);
-      item_list.push(list_item(item));
-      input = next_input;
-    } else {
+  while let Some(line) = list_line(input) {
+    // 표시가 다르거나 `#N`으로 다시 시작하면 새 목록이다
+    let is_same_list = line.indent == first_line.indent
+      && line.marker == first_line.marker
+      && (item_list.is_empty() || line.start.is_none());
+    if !is_same_list {
       break;
     }
+    let (next_input, content) = list_item_line(line.rest);
+    let mut block_list = if content.is_empty() {
+      vec![]
+    } else {
+      multiline_block_list(content, item_context)
+    };
+    input = next_input;
+
+    while indent_of(input) > first_line.indent {
+      let sublist = match item_context.nested() {
+        Some(context) if starts_with_list(input) => list_of_indent(input, context).ok(),
+        _ => None,
+      };
+      if let Some((next_input, sublist)) = sublist {
+        block_list.push(sublist);
+        input = next_input;
+      } else {
+        let (next_input, content) = list_item_line(&input[first_line.indent + 1..]);
+        append(&mut block_list, multiline_block_list(content, item_context));
+        input = next_input;
+      }
+    }
+
+    // 첫 항목이 비어 있으면 항목으로 치지 않는다
+    if !item_list.is_empty() || !content.is_empty() || !block_list.is_empty() {
+      item_list.push(ListItem(block_list));
+    }
   }
 
-  let block = MultilineBlock::UnorderedList(item_list);
+  let depth = item_context.list_depth();
+  let block = match first_line.marker {
+    "*" => MultilineBlock::UnorderedList(item_list, depth),
+    marker => {
+      let start = first_line.start.unwrap_or(1);
+      let list_index = match marker {
+        "1." => ListIndex::Numeric(start),
+        "a." => ListIndex::LowerAlphabet(start),
+        "A." => ListIndex::UpperAlphabet(start),
+        "i." => ListIndex::LowerRoman(start),
+        "I." => ListIndex::UpperRoman(start),
+        "ㄱ." => ListIndex::HangulChosung(start),
+        _ => ListIndex::HangulSyllable(start),
+      };
+      MultilineBlock::OrderedList(item_list, list_index, depth)
+    }
+  };
 
   Ok((input, block))
 }
 
-fn expect_unorder_list(input: &str) -> Result {
-  let (input, _) = tag(UNORDERED_LIST)(input)?;
-
-  Ok((input, ()))
-}
-
-fn starts_with_unordered_list(input: &str) -> bool {
-  input.starts_with(UNORDERED_LIST)
-}
-
-fn ordered_list(input: &str) -> Result<MultilineBlock> {
-  alt((
-    ordered_list_numeric,
-    ordered_list_lower_alphabet,
-    ordered_list_upper_alphabet,
-    ordered_list_lower_arabic,
-    ordered_list_upper_arabic,
-    ordered_list_hangul_chosung,
-    ordered_list_hangul_syllable,
-  ))(input)
+fn list_line(input: &str) -> Option<ListLine> {
+  let indent = indent_of(input);
+  if indent == 0 {
+    return None;
+  }
+  let rest = &input[indent..];
+  let marker = MARKER_LIST
+    .iter()
+    .find(|marker| rest.starts_with(*marker))?;
+  let (rest, start) = list_start(&rest[marker.len()..]).ok()?;
+
+  Some(ListLine {
+    indent,
+    marker,
+    start,
+    rest,
+  })
 }
 
-fn starts_with_ordered_list(input: &str) -> bool {
-  starts_with_ordered_list_numeric(input)
-    || starts_with_ordered_list_lower_alphabet(input)
-    || starts_with_ordered_list_upper_alphabet(input)
-    || starts_with_ordered_list_lower_arabic(input)
-    || starts_with_ordered_list_upper_arabic(input)
-    || starts_with_ordered_list_hangul_chosung(input)
-    || starts_with_ordered_list_hangul_syllable(input)
+fn indent_of(input: &str) -> usize {
+  input.bytes().take_while(|byte| *byte == b' ').count()
 }
 
-// TODO(Danuel): 함수를 재사용하도록 최적화 할 필요 있음
-macro_rules! ordered_list_type {
-  ($marker:expr, $variant:ident, $name:ident, $expect_with_name:ident, $starts_with_name:ident) => {
-    fn $name(input: &str) -> Result<MultilineBlock> {
-      let (input, _) = $expect_with_name(input)?;
-      let (input, cursor) = list_start(input)?;
-      let (mut input, item) = list_item_line(input);
-
-      let mut item_list = vec![];
-      if !item.is_empty() {
-        item_list.push(list_item(item))
-      }
-
-      while input.starts_with(' ') && starts_with_list(input) {
-        if let Ok((next_input, _)) = $expect_with_name(input) {
-          let (next_input, cursor) = list_start(next_input)?;
-          if cursor.is_some() {
-            break;
-          }
-          let (next_input, item) = list_item_line(next_input);
-          item_list.push(list_item(item));
-          input = next_input;
-        } else {
-          break;
-        }
-      }
-
-      let list_index = ListIndex::$variant(cursor.unwrap_or_else(|| "1"));
-      let block = MultilineBlock::OrderedList(item_list, list_index);
-
-      Ok((input, block))
-    }
-
-    fn $expect_with_name(input: &str) -> Result {
-      let (input, _) = tag($marker)(input)?;
-
-      Ok((input, ()))
-    }
-
-    fn $starts_with_name(input: &str) -> bool {
-      input.starts_with($marker)
+/// 이어지는 줄이 문단이면 앞 문단에 줄바꿈으로 붙인다
+fn append<'a>(block_list: &mut Vec<MultilineBlock<'a>>, next_list: Vec<MultilineBlock<'a>>) {
+  let mut next_list = next_list.into_iter();
+  match (block_list.last_mut(), next_list.next()) {
+    (Some(MultilineBlock::Paragraph(span_list)), Some(MultilineBlock::Paragraph(next))) => {
+      span_list.push(Span::Semantic(SemanticSpan::Linebreak));
+      span_list.extend(next);
     }
-  };
-}
-
-ordered_list_type!(
-  " 1.",
-  Numeric,
-  ordered_list_numeric,
-  expect_ordered_list_numeric,
-  starts_with_ordered_list_numeric
-);
-ordered_list_type!(
-  " a.",
-  LowerAlphabet,
-  ordered_list_lower_alphabet,
-  expect_ordered_list_lower_alphabet,
-  starts_with_ordered_list_lower_alphabet
-);
-ordered_list_type!(
-  " A.",
-  UpperAlphabet,
-  ordered_list_upper_alphabet,
-  expect_ordered_list_upper_alphabet,
-  starts_with_ordered_list_upper_alphabet
-);
-ordered_list_type!(
-  " i.",
-  LowerArabic,
-  ordered_list_lower_arabic,
-  expect_ordered_list_lower_arabic,
-  starts_with_ordered_list_lower_arabic
-);
-ordered_list_type!(
-  " I.",
-  UpperArabic,
-  ordered_list_upper_arabic,
-  expect_ordered_list_upper_arabic,
-  starts_with_ordered_list_upper_arabic
-);
-ordered_list_type!(
-  " ㄱ.",
-  HangulChosung,
-  ordered_list_hangul_chosung,
-  expect_ordered_list_hangul_chosung,
-  starts_with_ordered_list_hangul_chosung
-);
-ordered_list_type!(
-  " 가.",
-  HangulSyllable,
-  ordered_list_hangul_syllable,
-  expect_ordered_list_hangul_syllable,
-  starts_with_ordered_list_hangul_syllable
-);
-
-fn list_item(input: &str) -> ListItem {
-  ListItem(multiline_block_list(input))
+    (_, Some(block)) => block_list.push(block),
+    (_, None) => {}
+  }
+  block_list.extend(next_list);
 }
 
-fn list_start(input: &str) -> Result<Option<&str>> {
-  opt(|input| -> Result<&str> {
+fn list_start(input: &str) -> Result<Option<usize>> {
+  opt(|input| -> Result<usize> {
     let (input, _) = char('#')(input)?;
+    let (rest, start) = digit1(input)?;
+    let start = start
+      .parse()
+      .map_err(|_| Err::Error((input, ErrorKind::TooLarge)))?;
 
-    is_a("0123456789abcdefghijklmnopqrstuvwxyz")(input)
+    Ok((rest, start))
   })(input)
 }
 
@@ -211,11 +172,12 @@ mod unordered_list_tests {
     let source = " *Danuel";
     assert_eq!(
       parse(source),
-      vec![Block::Multiline(MultilineBlock::UnorderedList(vec![
-        ListItem(vec![MultilineBlock::Paragraph(vec![Span::Inline(
-          "Danuel"
-        )])])
-      ]))]
+      vec![Block::Multiline(MultilineBlock::UnorderedList(
+        vec![ListItem(vec![MultilineBlock::Paragraph(vec![
+          Span::Inline("Danuel".into())
+        ])])],
+        1
+      ))]
     )
   }
 
@@ -224,11 +186,12 @@ mod unordered_list_tests {
     let source = " * Danuel";
     assert_eq!(
       parse(source),
-      vec![Block::Multiline(MultilineBlock::UnorderedList(vec![
-        ListItem(vec![MultilineBlock::Paragraph(vec![Span::Inline(
-          "Danuel"
-        )])])
-      ]))]
+      vec![Block::Multiline(MultilineBlock::UnorderedList(
+        vec![ListItem(vec![MultilineBlock::Paragraph(vec![
+          Span::Inline("Danuel".into())
+        ])])],
+        1
+      ))]
     )
   }
 
@@ -237,11 +200,12 @@ mod unordered_list_tests {
     let source = " *  Danuel";
     assert_eq!(
       parse(source),
-      vec![Block::Multiline(MultilineBlock::UnorderedList(vec![
-        ListItem(vec![MultilineBlock::Indent(vec![
-          MultilineBlock::Paragraph(vec![Span::Inline("Danuel")])
-        ])])
-      ]))]
+      vec![Block::Multiline(MultilineBlock::UnorderedList(
+        vec![ListItem(vec![MultilineBlock::Indent(vec![
+          MultilineBlock::Paragraph(vec![Span::Inline("Danuel".into())])
+        ])])],
+        1
+      ))]
     )
   }
 
@@ -252,10 +216,15 @@ Danuel";
     assert_eq!(
       parse(source),
       vec![
-        Block::Multiline(MultilineBlock::UnorderedList(vec![ListItem(vec![
-          MultilineBlock::Paragraph(vec![Span::Inline("Danuel")])
-        ])])),
-        Block::Multiline(MultilineBlock::Paragraph(vec![Span::Inline("Danuel")]))
+        Block::Multiline(MultilineBlock::UnorderedList(
+          vec![ListItem(vec![MultilineBlock::Paragraph(vec![
+            Span::Inline("Danuel".into())
+          ])])],
+          1
+        )),
+        Block::Multiline(MultilineBlock::Paragraph(vec![Span::Inline(
+          "Danuel".into()
+        )]))
       ]
     )
   }
@@ -266,18 +235,203 @@ Danuel";
  *Danuel";
     assert_eq!(
       parse(source),
-      vec![Block::Multiline(MultilineBlock::UnorderedList(vec![
-        ListItem(vec![MultilineBlock::Paragraph(vec![Span::Inline(
-          "Danuel"
-        )])]),
-        ListItem(vec![MultilineBlock::Paragraph(vec![Span::Inline(
-          "Danuel"
-        )])])
-      ]))]
+      vec![Block::Multiline(MultilineBlock::UnorderedList(
+        vec![
+          ListItem(vec![MultilineBlock::Paragraph(vec![Span::Inline(
+            "Danuel".into()
+          )])]),
+          ListItem(vec![MultilineBlock::Paragraph(vec![Span::Inline(
+            "Danuel".into()
+          )])])
+        ],
+        1
+      ))]
     )
   }
 }
 
+#[cfg(test)]
+mod nested_list_tests {
+  use crate::*;
+
+  fn paragraph(text: &str) -> MultilineBlock {
+    MultilineBlock::Paragraph(vec![Span::Inline(text.into())])
+  }
+
+  #[test]
+  fn sublist() {
+    let source = " * a\n  * b\n   * c\n  * d\n * e";
+    assert_eq!(
+      parse(source),
+      vec![Block::Multiline(MultilineBlock::UnorderedList(
+        vec![
+          ListItem(vec![
+            paragraph("a"),
+            MultilineBlock::UnorderedList(
+              vec![
+                ListItem(vec![
+                  paragraph("b"),
+                  MultilineBlock::UnorderedList(vec![ListItem(vec![paragraph("c")])], 3)
+                ]),
+                ListItem(vec![paragraph("d")])
+              ],
+              2
+            )
+          ]),
+          ListItem(vec![paragraph("e")])
+        ],
+        1
+      ))]
+    );
+  }
+
+  #[test]
+  fn mixed_sublist() {
+    let source = " 1. a\n  * b\n  * c\n 1. d\n  a. e";
+    assert_eq!(
+      parse(source),
+      vec![Block::Multiline(MultilineBlock::OrderedList(
+        vec![
+          ListItem(vec![
+            paragraph("a"),
+            MultilineBlock::UnorderedList(
+              vec![
+                ListItem(vec![paragraph("b")]),
+                ListItem(vec![paragraph("c")])
+              ],
+              2
+            )
+          ]),
+          ListItem(vec![
+            paragraph("d"),
+            MultilineBlock::OrderedList(
+              vec![ListItem(vec![paragraph("e")])],
+              ListIndex::LowerAlphabet(1),
+              2
+            )
+          ])
+        ],
+        ListIndex::Numeric(1),
+        1
+      ))]
+    );
+  }
+
+  #[test]
+  fn continuation_line() {
+    let source = " * a\n  b\n  * c\n  d\n   e";
+    assert_eq!(
+      parse(source),
+      vec![Block::Multiline(MultilineBlock::UnorderedList(
+        vec![ListItem(vec![
+          MultilineBlock::Paragraph(vec![
+            Span::Inline("a".into()),
+            Span::Semantic(SemanticSpan::Linebreak),
+            Span::Inline("b".into())
+          ]),
+          MultilineBlock::UnorderedList(vec![ListItem(vec![paragraph("c")])], 2),
+          MultilineBlock::Paragraph(vec![
+            Span::Inline("d".into()),
+            Span::Semantic(SemanticSpan::Linebreak),
+            Span::Inline("e".into())
+          ])
+        ])],
+        1
+      ))]
+    );
+  }
+
+  #[test]
+  fn restart_per_depth() {
+    let source = " 1. a\n  1.#3 b\n  1. c\n  1.#7 d\n 1.#5 e";
+    assert_eq!(
+      parse(source),
+      vec![
+        Block::Multiline(MultilineBlock::OrderedList(
+          vec![ListItem(vec![
+            paragraph("a"),
+            MultilineBlock::OrderedList(
+              vec![
+                ListItem(vec![paragraph("b")]),
+                ListItem(vec![paragraph("c")])
+              ],
+              ListIndex::Numeric(3),
+              2
+            ),
+            MultilineBlock::OrderedList(
+              vec![ListItem(vec![paragraph("d")])],
+              ListIndex::Numeric(7),
+              2
+            )
+          ])],
+          ListIndex::Numeric(1),
+          1
+        )),
+        Block::Multiline(MultilineBlock::OrderedList(
+          vec![ListItem(vec![paragraph("e")])],
+          ListIndex::Numeric(5),
+          1
+        ))
+      ]
+    );
+  }
+
+  #[test]
+  fn sibling_list_with_other_marker() {
+    assert_eq!(
+      parse(" * a\n 1. b"),
+      vec![
+        Block::Multiline(MultilineBlock::UnorderedList(
+          vec![ListItem(vec![paragraph("a")])],
+          1
+        )),
+        Block::Multiline(MultilineBlock::OrderedList(
+          vec![ListItem(vec![paragraph("b")])],
+          ListIndex::Numeric(1),
+          1
+        ))
+      ]
+    );
+  }
+
+  #[test]
+  fn indented_first_line() {
+    assert_eq!(
+      parse("foo\n  * a"),
+      vec![
+        Block::Multiline(MultilineBlock::Paragraph(vec![
+          Span::Inline("foo".into()),
+          Span::Semantic(SemanticSpan::Linebreak)
+        ])),
+        Block::Multiline(MultilineBlock::UnorderedList(
+          vec![ListItem(vec![paragraph("a")])],
+          1
+        ))
+      ]
+    );
+  }
+
+  #[test]
+  fn non_numeric_start() {
+    assert_eq!(
+      parse(" a.#b foo"),
+      vec![Block::Multiline(MultilineBlock::OrderedList(
+        vec![ListItem(vec![paragraph("#b foo")])],
+        ListIndex::LowerAlphabet(1),
+        1
+      ))]
+    );
+  }
+
+  #[test]
+  fn deep_list() {
+    let source: String = (1..=200)
+      .map(|depth| format!("{}* a\n", " ".repeat(depth)))
+      .collect();
+    assert!(!parse(&source).is_empty());
+  }
+}
+
 macro_rules! ordered_list_tests {
   (
     $name:ident,
@@ -302,9 +456,10 @@ macro_rules! ordered_list_tests {
           parse(source),
           vec![Block::Multiline(MultilineBlock::OrderedList(
             vec![ListItem(vec![MultilineBlock::Paragraph(vec![
-              Span::Inline("foo")
+              Span::Inline("foo".into())
             ])])],
-            ListIndex::$variant("1")
+            ListIndex::$variant(1),
+            1
           ))]
         )
       }
@@ -316,9 +471,10 @@ macro_rules! ordered_list_tests {
           parse(source),
           vec![Block::Multiline(MultilineBlock::OrderedList(
             vec![ListItem(vec![MultilineBlock::Paragraph(vec![
-              Span::Inline("foo")
+              Span::Inline("foo".into())
             ])])],
-            ListIndex::$variant("1")
+            ListIndex::$variant(1),
+            1
           ))]
         )
       }
@@ -330,9 +486,10 @@ macro_rules! ordered_list_tests {
           parse(source),
           vec![Block::Multiline(MultilineBlock::OrderedList(
             vec![ListItem(vec![MultilineBlock::Indent(vec![
-              MultilineBlock::Paragraph(vec![Span::Inline("foo")])
+              MultilineBlock::Paragraph(vec![Span::Inline("foo".into())])
             ])])],
-            ListIndex::$variant("1")
+            ListIndex::$variant(1),
+            1
           ))]
         )
       }
@@ -344,10 +501,11 @@ macro_rules! ordered_list_tests {
           parse(source),
           vec![Block::Multiline(MultilineBlock::OrderedList(
             vec![ListItem(vec![MultilineBlock::Paragraph(vec![
-              Span::Inline("foo "),
-              Span::Bracket(BracketSpan::Inline("bar"))
+              Span::Inline("foo ".into()),
+              Span::Bracket(BracketSpan::Inline("bar".into()))
             ])])],
-            ListIndex::$variant("1")
+            ListIndex::$variant(1),
+            1
           ))]
         )
       }
@@ -359,10 +517,11 @@ macro_rules! ordered_list_tests {
           parse(source),
           vec![Block::Multiline(MultilineBlock::OrderedList(
             vec![ListItem(vec![MultilineBlock::Paragraph(vec![
-              Span::Inline("foo "),
-              Span::Bracket(BracketSpan::Inline("\nbar\n"))
+              Span::Inline("foo ".into()),
+              Span::Bracket(BracketSpan::Inline("\nbar\n".into()))
             ])])],
-            ListIndex::$variant("1")
+            ListIndex::$variant(1),
+            1
           ))]
         )
       }
@@ -374,9 +533,10 @@ macro_rules! ordered_list_tests {
           parse(source),
           vec![Block::Multiline(MultilineBlock::OrderedList(
             vec![ListItem(vec![MultilineBlock::Paragraph(vec![
-              Span::Inline("foo")
+              Span::Inline("foo".into())
             ])])],
-            ListIndex::$variant("4")
+            ListIndex::$variant(4),
+            1
           ))]
         )
       }
@@ -389,11 +549,12 @@ macro_rules! ordered_list_tests {
           vec![
             Block::Multiline(MultilineBlock::OrderedList(
               vec![ListItem(vec![MultilineBlock::Paragraph(vec![
-                Span::Inline("foo")
+                Span::Inline("foo".into())
               ])])],
-              ListIndex::$variant("1")
+              ListIndex::$variant(1),
+              1
             )),
-            Block::Multiline(MultilineBlock::Paragraph(vec![Span::Inline("bar")],))
+            Block::Multiline(MultilineBlock::Paragraph(vec![Span::Inline("bar".into())],))
           ]
         )
       }
@@ -405,10 +566,15 @@ macro_rules! ordered_list_tests {
           parse(source),
           vec![Block::Multiline(MultilineBlock::OrderedList(
             vec![
-              ListItem(vec![MultilineBlock::Paragraph(vec![Span::Inline("foo")])],),
-              ListItem(vec![MultilineBlock::Paragraph(vec![Span::Inline("bar")])])
+              ListItem(vec![MultilineBlock::Paragraph(vec![Span::Inline(
+                "foo".into()
+              )])],),
+              ListItem(vec![MultilineBlock::Paragraph(vec![Span::Inline(
+                "bar".into()
+              )])])
             ],
-            ListIndex::$variant("1")
+            ListIndex::$variant(1),
+            1
           ))]
         )
       }
@@ -497,8 +663,8 @@ bar",
  A.bar"
 }
 ordered_list_tests! {
-  ordered_list_lower_arabic_tests,
-  LowerArabic,
+  ordered_list_lower_roman_tests,
+  LowerRoman,
   " i.foo",
   " i. foo",
   " i.  foo",
@@ -513,8 +679,8 @@ bar",
  i.bar"
 }
 ordered_list_tests! {
-  ordered_list_upper_arabic_tests,
-  UpperArabic,
+  ordered_list_upper_roman_tests,
+  UpperRoman,
   " I.foo",
   " I. foo",
   " I.  foo",
@@ -0,0 +1,173 @@
+const HANGUL_CHOSUNG_LIST: [char; 14] = [
+  'ㄱ', 'ㄴ', 'ㄷ', 'ㄹ', 'ㅁ', 'ㅂ', 'ㅅ', 'ㅇ', 'ㅈ', 'ㅊ', 'ㅋ', 'ㅌ', 'ㅍ', 'ㅎ',
+];
+const HANGUL_SYLLABLE_LIST: [char; 14] = [
+  '가', '나', '다', '라', '마', '바', '사', '아', '자', '차', '카', '타', '파', '하',
+];
+const LOWER_ALPHABET_LIST: [char; 26] = [
+  'a', 'b', 'c', 'd', 'e', 'f', 'g', 'h', 'i', 'j', 'k', 'l', 'm', 'n', 'o', 'p', 'q', 'r', 's',
+  't', 'u', 'v', 'w', 'x', 'y', 'z',
+];
+const ROMAN_LIST: [(usize, &str); 13] = [
+  (1000, "M"),
+  (900, "CM"),
+  (500, "D"),
+  (400, "CD"),
+  (100, "C"),
+  (90, "XC"),
+  (50, "L"),
+  (40, "XL"),
+  (10, "X"),
+  (9, "IX"),
+  (5, "V"),
+  (4, "IV"),
+  (1, "I"),
+];
+/// 로마 숫자로 나타낼 수 있는 가장 큰 수
+const ROMAN_MAX: usize = 3999;
+
+/// 순서 있는 목록의 번호 모양과 시작 번호
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ListIndex {
+  /// `1.`
+  Numeric(usize),
+  /// `ㄱ.`
+  HangulChosung(usize),
+  /// `가.`
+  HangulSyllable(usize),
+  /// `a.`
+  LowerAlphabet(usize),
+  /// `A.`
+  UpperAlphabet(usize),
+  /// `i.`
+  LowerRoman(usize),
+  /// `I.`
+  UpperRoman(usize),
+}
+
+impl ListIndex {
+  pub fn start(self) -> usize {
+    match self {
+      ListIndex::Numeric(start)
+      | ListIndex::HangulChosung(start)
+      | ListIndex::HangulSyllable(start)
+      | ListIndex::LowerAlphabet(start)
+      | ListIndex::UpperAlphabet(start)
+      | ListIndex::LowerRoman(start)
+      | ListIndex::UpperRoman(start) => start,
+    }
+  }
+
+  /// `position`번째 항목(0부터 센다)의 번호.
+  /// 글자로 셀 수 없는 번호(0, 로마 숫자로는 3999 초과)는 아라비아 숫자로 나타낸다
+  pub fn label(self, position: usize) -> String {
+    let number = self.start().saturating_add(position);
+    let label = match self {
+      ListIndex::Numeric(_) => None,
+      ListIndex::HangulChosung(_) => letters(number, &HANGUL_CHOSUNG_LIST),
+      ListIndex::HangulSyllable(_) => letters(number, &HANGUL_SYLLABLE_LIST),
+      ListIndex::LowerAlphabet(_) => letters(number, &LOWER_ALPHABET_LIST),
+      ListIndex::UpperAlphabet(_) => {
+        letters(number, &LOWER_ALPHABET_LIST).map(|label| label.to_ascii_uppercase())
+      }
+      ListIndex::LowerRoman(_) => roman(number).map(|label| label.to_ascii_lowercase()),
+      ListIndex::UpperRoman(_) => roman(number),
+    };
+
+    label.unwrap_or_else(|| number.to_string())
+  }
+}
+
+/// 자릿값이 1부터 시작하는 진법으로 센다. 마지막 글자 다음은 첫 글자 두 개이다
+fn letters(number: usize, letter_list: &[char]) -> Option<String> {
+  if number == 0 {
+    return None;
+  }
+
+  let mut number = number;
+  let mut letter_list_reversed = vec![];
+  while number > 0 {
+    number -= 1;
+    letter_list_reversed.push(letter_list[number % letter_list.len()]);
+    number /= letter_list.len();
+  }
+
+  Some(letter_list_reversed.into_iter().rev().collect())
+}
+
+fn roman(number: usize) -> Option<String> {
+  if number == 0 || number > ROMAN_MAX {
+    return None;
+  }
+
+  let mut number = number;
+  let mut label = String::new();
+  for (value, symbol) in ROMAN_LIST.iter() {
+    while number >= *value {
+      label.push_str(symbol);
+      number -= value;
+    }
+  }
+
+  Some(label)
+}
+
+#[cfg(test)]
+mod tests {
+  use crate::*;
+
+  fn label_list(index: ListIndex, count: usize) -> Vec<String> {
+    (0..count).map(|position| index.label(position)).collect()
+  }
+
+  #[test]
+  fn numeric() {
+    assert_eq!(label_list(ListIndex::Numeric(1), 3), ["1", "2", "3"]);
+    assert_eq!(label_list(ListIndex::Numeric(0), 2), ["0", "1"]);
+    assert_eq!(ListIndex::Numeric(9).label(1), "10");
+  }
+
+  #[test]
+  fn hangul() {
+    assert_eq!(
+      label_list(ListIndex::HangulChosung(1), 3),
+      ["ㄱ", "ㄴ", "ㄷ"]
+    );
+    assert_eq!(
+      label_list(ListIndex::HangulSyllable(13), 3),
+      ["파", "하", "가가"]
+    );
+    assert_eq!(ListIndex::HangulChosung(14).label(1), "ㄱㄱ");
+  }
+
+  #[test]
+  fn alphabet() {
+    assert_eq!(label_list(ListIndex::LowerAlphabet(1), 3), ["a", "b", "c"]);
+    assert_eq!(
+      label_list(ListIndex::LowerAlphabet(25), 4),
+      ["y", "z", "aa", "ab"]
+    );
+    assert_eq!(ListIndex::UpperAlphabet(52).label(0), "AZ");
+    assert_eq!(ListIndex::UpperAlphabet(702).label(1), "AAA");
+    assert_eq!(ListIndex::LowerAlphabet(0).label(0), "0");
+  }
+
+  #[test]
+  fn roman() {
+    assert_eq!(
+      label_list(ListIndex::LowerRoman(1), 10),
+      ["i", "ii", "iii", "iv", "v", "vi", "vii", "viii", "ix", "x"]
+    );
+    assert_eq!(ListIndex::UpperRoman(1994).label(0), "MCMXCIV");
+    assert_eq!(ListIndex::UpperRoman(3999).label(1), "4000");
+    assert_eq!(ListIndex::UpperRoman(0).label(0), "0");
+  }
+
+  #[test]
+  fn overflow() {
+    assert_eq!(
+      ListIndex::Numeric(usize::MAX).label(1),
+      usize::MAX.to_string()
+    );
+  }
+}
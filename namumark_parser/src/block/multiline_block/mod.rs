@@ -2,13 +2,15 @@ pub(crate) mod blockquote;
 pub(crate) mod horizontal_rule;
 mod indent;
 pub(crate) mod list;
+mod list_index;
 mod paragraph;
 
-use crate::{Result, Span};
+use crate::{Context, Result, Span};
 use blockquote::blockquote;
 use horizontal_rule::horizontal_rule;
 use indent::indent;
 use list::list;
+pub use list_index::ListIndex;
 use nom::branch::alt;
 use paragraph::paragraph;
 
@@ -17,20 +19,11 @@ pub enum MultilineBlock<'a> {
   Blockquote(Vec<MultilineBlock<'a>>),
   HorizontalRule,
   Indent(Vec<MultilineBlock<'a>>),
-  OrderedList(Vec<ListItem<'a>>, ListIndex<'a>),
+  /// 항목, 시작 번호, 중첩 깊이. 중첩 깊이는 바깥 목록의 수에 1을 더한 값이다
+  OrderedList(Vec<ListItem<'a>>, ListIndex, usize),
   Paragraph(Vec<Span<'a>>),
-  UnorderedList(Vec<ListItem<'a>>),
-}
-
-#[derive(Debug, PartialEq)]
-pub enum ListIndex<'a> {
-  Numeric(&'a str),
-  HangulChosung(&'a str),
-  HangulSyllable(&'a str),
-  LowerAlphabet(&'a str),
-  UpperAlphabet(&'a str),
-  LowerArabic(&'a str),
-  UpperArabic(&'a str),
+  /// 항목과 중첩 깊이
+  UnorderedList(Vec<ListItem<'a>>, usize),
 }
 
 #[derive(Debug, PartialEq)]
@@ -51,15 +44,31 @@ impl<'a> IntoIterator for ListItem<'a> {
   }
 }
 
-pub(crate) fn multiline_block(input: &str) -> Result<MultilineBlock> {
-  alt((list, indent, horizontal_rule, blockquote, paragraph))(input)
+pub(crate) fn multiline_block<'a>(
+  input: &'a str,
+  context: Context,
+) -> Result<'a, MultilineBlock<'a>> {
+  alt((
+    |input| list(input, context),
+    |input| indent(input, context),
+    |input| horizontal_rule(input, context),
+    |input| blockquote(input, context),
+    |input| paragraph(input, context),
+  ))(input)
 }
 
-pub(crate) fn multiline_block_list(input: &str) -> Vec<MultilineBlock> {
+pub(crate) fn multiline_block_list<'a>(
+  input: &'a str,
+  context: Context,
+) -> Vec<MultilineBlock<'a>> {
+  let context = match context.nested() {
+    Some(context) => context,
+    None => return vec![MultilineBlock::Paragraph(vec![Span::Inline(input.into())])],
+  };
   let mut input = input;
   let mut block_list = vec![];
 
-  while let Ok((next_input, block)) = multiline_block(input) {
+  while let Ok((next_input, block)) = multiline_block(input, context) {
     block_list.push(block);
     if next_input.is_empty() {
       break;
@@ -4,37 +4,40 @@ use super::{
   horizontal_rule::starts_with_horizontal_rule,
   list::starts_with_list,
 };
-use crate::{line_with_bracket, linebreak, span_list, whitespace, MultilineBlock, Result};
+use crate::{
+  lexer::find_unescaped, line_with_bracket, linebreak, span_list_with, whitespace, Context,
+  MultilineBlock, Result, Syntax,
+};
+use memchr::memchr;
 
-pub(crate) fn paragraph(input: &str) -> Result<MultilineBlock> {
-  let (input, line) = line(input);
-  let span_list = span_list(line);
+pub(crate) fn paragraph<'a>(input: &'a str, context: Context) -> Result<'a, MultilineBlock<'a>> {
+  let (input, line) = line(input, context);
+  let span_list = span_list_with(line, context);
   let block = MultilineBlock::Paragraph(span_list);
 
   Ok((input, block))
 }
 
-fn line(input: &str) -> (&str, &str) {
+/// 다른 블록으로 파싱되지 못해 호출된 것이므로 첫 줄은 항상 문단에 포함한다
+fn line<'a>(input: &'a str, context: Context) -> (&'a str, &'a str) {
   let mut index = 0;
   while index < input.len() {
     if let Some(slice) = input.get(index..) {
-      if starts_with_other_block(slice) {
+      if index > 0 && starts_with_other_block(slice, context) {
         break;
       } else if slice.starts_with('\n') {
         index += 1;
       } else {
-        while let Some(next_line) = input.get(index..) {
-          if let Some(line) = next_line.lines().next() {
-            if let Some(bracket_index) = line.find("{{{") {
-              index += bracket_index;
-              let (_, bracket_line) = line_with_bracket(&input[index..]);
-              index += bracket_line.len();
-            } else {
-              index += line.len();
-              break;
-            }
+        // 줄 안에 `{{{`가 있으면 짝이 닫힐 때까지 줄바꿈을 넘어 이어 붙인다
+        loop {
+          let rest = &input.as_bytes()[index..];
+          let line_end = memchr(b'\n', rest).unwrap_or(rest.len());
+          if let Some(bracket_index) = find_unescaped(&rest[..line_end], b"{{{") {
+            index += bracket_index;
+            let (_, bracket_line) = line_with_bracket(&input[index..]);
+            index += bracket_line.len();
           } else {
-            index += 1;
+            index += line_end;
             break;
           }
         }
@@ -47,12 +50,15 @@ fn line(input: &str) -> (&str, &str) {
   (linebreak(&input[index..]), whitespace(&input[..index]))
 }
 
-fn starts_with_other_block(input: &str) -> bool {
-  starts_with_blockquote(input)
-    || starts_with_horizontal_rule(input)
-    || starts_with_list(input)
-    || starts_with_comment(input)
-    || starts_with_open_heading(input)
+fn starts_with_other_block(input: &str, context: Context) -> bool {
+  let starts_with =
+    |syntax, starts_with: fn(&str) -> bool| context.is_enabled(syntax) && starts_with(input);
+
+  starts_with(Syntax::Blockquote, starts_with_blockquote)
+    || starts_with(Syntax::HorizontalRule, starts_with_horizontal_rule)
+    || starts_with(Syntax::List, starts_with_list)
+    || starts_with(Syntax::Comment, starts_with_comment)
+    || starts_with(Syntax::Heading, starts_with_open_heading)
 }
 
 #[cfg(test)]
@@ -65,7 +71,30 @@ mod tests {
     assert_eq!(
       parse(source),
       vec![Block::Multiline(MultilineBlock::Paragraph(vec![
-        Span::Inline("Danuel")
+        Span::Inline("Danuel".into())
+      ]))]
+    );
+  }
+
+  #[test]
+  fn unclosed_bracket() {
+    let source = "Danuel {{{";
+    assert_eq!(
+      parse(source),
+      vec![Block::Multiline(MultilineBlock::Paragraph(vec![
+        Span::Inline("Danuel {{{".into())
+      ]))]
+    );
+  }
+
+  #[test]
+  fn crlf() {
+    assert_eq!(
+      parse("a\r\nb"),
+      vec![Block::Multiline(MultilineBlock::Paragraph(vec![
+        Span::Inline("a\r".into()),
+        Span::Semantic(SemanticSpan::Linebreak),
+        Span::Inline("b".into())
       ]))]
     );
   }
@@ -1,15 +1,21 @@
-use crate::{line, span_list, Result, SinglelineBlock};
+use crate::{line, span_list_with, Context, Result, SinglelineBlock, Syntax};
 use nom::{
   bytes::complete::tag,
   character::complete::char,
+  error::ErrorKind,
   multi::{count, fold_many_m_n},
+  Err,
 };
 
-pub(crate) fn closed_heading(input: &str) -> Result<SinglelineBlock> {
+pub(crate) fn closed_heading<'a>(
+  input: &'a str,
+  context: Context,
+) -> Result<'a, SinglelineBlock<'a>> {
+  let (input, _) = context.expect(input, Syntax::Heading)?;
   let (input, line) = line(input);
   let (line, level) = start(line)?;
   let (line, _) = end(line, level)?;
-  let span_list = span_list(line);
+  let span_list = span_list_with(line, context);
   let block = SinglelineBlock::ClosedHeading(span_list, level.into());
 
   Ok((input, block))
@@ -23,7 +29,11 @@ fn start(input: &str) -> Result<usize> {
 }
 
 fn end(input: &str, level: usize) -> Result {
-  let marker_position = input.len() - level - 2;
+  let marker_position = input
+    .len()
+    .checked_sub(level + 2)
+    .filter(|position| input.is_char_boundary(*position))
+    .ok_or(Err::Error((input, ErrorKind::Verify)))?;
   let (input, tail) = (&input[..marker_position], &input[marker_position..]);
   let (tail, _) = tag(" #")(tail)?;
   let _ = count(char('='), level)(tail)?;
@@ -41,7 +51,7 @@ mod tests {
     assert_eq!(
       parse(source),
       vec![Block::Singleline(SinglelineBlock::ClosedHeading(
-        vec![Span::Inline("Danuel")],
+        vec![Span::Inline("Danuel".into())],
         HeadingLevel::One
       ))]
     );
@@ -53,7 +63,7 @@ mod tests {
     assert_eq!(
       parse(source),
       vec![Block::Singleline(SinglelineBlock::ClosedHeading(
-        vec![Span::Inline("Danuel")],
+        vec![Span::Inline("Danuel".into())],
         HeadingLevel::Two
       ))]
     );
@@ -65,7 +75,7 @@ mod tests {
     assert_eq!(
       parse(source),
       vec![Block::Singleline(SinglelineBlock::ClosedHeading(
-        vec![Span::Inline("Danuel")],
+        vec![Span::Inline("Danuel".into())],
         HeadingLevel::Three
       ))]
     );
@@ -77,7 +87,7 @@ mod tests {
     assert_eq!(
       parse(source),
       vec![Block::Singleline(SinglelineBlock::ClosedHeading(
-        vec![Span::Inline("Danuel")],
+        vec![Span::Inline("Danuel".into())],
         HeadingLevel::Four
       ))]
     );
@@ -89,7 +99,7 @@ mod tests {
     assert_eq!(
       parse(source),
       vec![Block::Singleline(SinglelineBlock::ClosedHeading(
-        vec![Span::Inline("Danuel")],
+        vec![Span::Inline("Danuel".into())],
         HeadingLevel::Five
       ))]
     );
@@ -101,7 +111,7 @@ mod tests {
     assert_eq!(
       parse(source),
       vec![Block::Singleline(SinglelineBlock::ClosedHeading(
-        vec![Span::Inline("Danuel")],
+        vec![Span::Inline("Danuel".into())],
         HeadingLevel::Six
       ))]
     );
@@ -1,10 +1,11 @@
-use crate::{line, Result, SinglelineBlock};
+use crate::{line, Context, Result, SinglelineBlock, Syntax};
 use nom::bytes::complete::tag;
 
-pub(crate) fn comment(input: &str) -> Result<SinglelineBlock> {
+pub(crate) fn comment<'a>(input: &'a str, context: Context) -> Result<'a, SinglelineBlock<'a>> {
+  let (input, _) = context.expect(input, Syntax::Comment)?;
   let (input, line) = line(input);
   let (line, _) = start(line)?;
-  let block = SinglelineBlock::Comment(line);
+  let block = SinglelineBlock::Comment(line.into());
 
   Ok((input, block))
 }
@@ -28,7 +29,7 @@ mod tests {
     let source = "##Danuel";
     assert_eq!(
       parse(source),
-      vec![Block::Singleline(SinglelineBlock::Comment("Danuel"))]
+      vec![Block::Singleline(SinglelineBlock::Comment("Danuel".into()))]
     );
   }
 }
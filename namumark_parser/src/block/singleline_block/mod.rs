@@ -2,20 +2,21 @@ mod closed_heading;
 pub(crate) mod comment;
 pub(crate) mod open_heading;
 
-use crate::{Result, Span};
+use crate::{Context, Result, Span};
 use closed_heading::closed_heading;
 use comment::comment;
 use nom::branch::alt;
 use open_heading::open_heading;
+use std::borrow::Cow;
 
 #[derive(Debug, PartialEq)]
 pub enum SinglelineBlock<'a> {
   OpenHeading(Vec<Span<'a>>, HeadingLevel),
   ClosedHeading(Vec<Span<'a>>, HeadingLevel),
-  Comment(&'a str),
+  Comment(Cow<'a, str>),
 }
 
-#[derive(Debug, PartialEq)]
+#[derive(Debug, Clone, Copy, PartialEq)]
 pub enum HeadingLevel {
   One,
   Two,
@@ -41,6 +42,13 @@ impl From<usize> for HeadingLevel {
   }
 }
 
-pub(crate) fn singleline_block(input: &str) -> Result<SinglelineBlock> {
-  alt((open_heading, closed_heading, comment))(input)
+pub(crate) fn singleline_block<'a>(
+  input: &'a str,
+  context: Context,
+) -> Result<'a, SinglelineBlock<'a>> {
+  alt((
+    |input| open_heading(input, context),
+    |input| closed_heading(input, context),
+    |input| comment(input, context),
+  ))(input)
 }
@@ -1,13 +1,19 @@
-use crate::{line, span_list, Result, SinglelineBlock};
+use crate::{line, span_list_with, Context, Result, SinglelineBlock, Syntax};
 use nom::{
   character::complete::char,
+  error::ErrorKind,
   multi::{count, fold_many_m_n},
+  Err,
 };
 
-pub(crate) fn open_heading(input: &str) -> Result<SinglelineBlock> {
+pub(crate) fn open_heading<'a>(
+  input: &'a str,
+  context: Context,
+) -> Result<'a, SinglelineBlock<'a>> {
+  let (input, _) = context.expect(input, Syntax::Heading)?;
   let (input, line) = line(input);
   let (line, level) = parens(line)?;
-  let span_list = span_list(line);
+  let span_list = span_list_with(line, context);
   let block = SinglelineBlock::OpenHeading(span_list, level.into());
 
   Ok((input, block))
@@ -28,7 +34,11 @@ fn start(input: &str) -> Result<usize> {
 }
 
 fn end(input: &str, level: usize) -> Result {
-  let marker_position = input.len() - level - 1;
+  let marker_position = input
+    .len()
+    .checked_sub(level + 1)
+    .filter(|position| input.is_char_boundary(*position))
+    .ok_or(Err::Error((input, ErrorKind::Verify)))?;
   let (input, tail) = (&input[..marker_position], &input[marker_position..]);
   let (tail, _) = char(' ')(tail)?;
   let _ = count(char('='), level)(tail)?;
@@ -52,7 +62,7 @@ mod tests {
     assert_eq!(
       parse(source),
       vec![Block::Singleline(SinglelineBlock::OpenHeading(
-        vec![Span::Inline("Danuel")],
+        vec![Span::Inline("Danuel".into())],
         HeadingLevel::One
       ))]
     );
@@ -64,7 +74,7 @@ mod tests {
     assert_eq!(
       parse(source),
       vec![Block::Singleline(SinglelineBlock::OpenHeading(
-        vec![Span::Inline("Danuel")],
+        vec![Span::Inline("Danuel".into())],
         HeadingLevel::Two
       ))]
     );
@@ -76,7 +86,7 @@ mod tests {
     assert_eq!(
       parse(source),
       vec![Block::Singleline(SinglelineBlock::OpenHeading(
-        vec![Span::Inline("Danuel")],
+        vec![Span::Inline("Danuel".into())],
         HeadingLevel::Three
       ))]
     );
@@ -88,7 +98,7 @@ mod tests {
     assert_eq!(
       parse(source),
       vec![Block::Singleline(SinglelineBlock::OpenHeading(
-        vec![Span::Inline("Danuel")],
+        vec![Span::Inline("Danuel".into())],
         HeadingLevel::Four
       ))]
     );
@@ -100,7 +110,7 @@ mod tests {
     assert_eq!(
       parse(source),
       vec![Block::Singleline(SinglelineBlock::OpenHeading(
-        vec![Span::Inline("Danuel")],
+        vec![Span::Inline("Danuel".into())],
         HeadingLevel::Five
       ))]
     );
@@ -112,7 +122,7 @@ mod tests {
     assert_eq!(
       parse(source),
       vec![Block::Singleline(SinglelineBlock::OpenHeading(
-        vec![Span::Inline("Danuel")],
+        vec![Span::Inline("Danuel".into())],
         HeadingLevel::Six
       ))]
     );
@@ -0,0 +1,128 @@
+use crate::{block::block_list, line, Block, Context, PageTitle, ParseOptions, Syntax};
+use std::borrow::Cow;
+
+const REDIRECT_KEYWORD_LIST: [&str; 2] = ["#redirect", "#넘겨주기"];
+const ANCHOR: char = '#';
+
+/// 첫 줄이 `#redirect`인 문서와 일반 문서를 구분한 결과
+#[derive(Debug, PartialEq)]
+pub enum Document<'a> {
+  Redirect(Redirect<'a>),
+  Content(Vec<Block<'a>>),
+}
+
+/// `#redirect 문서#s-2`
+#[derive(Debug, Clone, PartialEq)]
+pub struct Redirect<'a> {
+  pub title: PageTitle<'a>,
+  /// `#` 뒤의 문단 이름
+  pub anchor: Option<Cow<'a, str>>,
+}
+
+pub fn parse_document(input: &str) -> Document<'_> {
+  parse_document_with(input, &Default::default())
+}
+
+pub fn parse_document_with<'a>(input: &'a str, options: &ParseOptions) -> Document<'a> {
+  match redirect_with(input, options) {
+    Some(redirect) => Document::Redirect(redirect),
+    None => Document::Content(block_list(input, Context::new(options))),
+  }
+}
+
+/// 첫 줄만 읽으므로 문서 전체를 파싱하지 않고 넘겨주기 문서인지 확인할 수 있다
+pub fn redirect(input: &str) -> Option<Redirect<'_>> {
+  redirect_with(input, &Default::default())
+}
+
+pub fn redirect_with<'a>(input: &'a str, options: &ParseOptions) -> Option<Redirect<'a>> {
+  if !options.is_enabled(Syntax::Redirect) {
+    return None;
+  }
+  let (_, line) = line(input.trim_start_matches('\u{feff}'));
+  let target = REDIRECT_KEYWORD_LIST.iter().find_map(|keyword| {
+    let prefix = line.get(..keyword.len())?;
+    let rest = &line[keyword.len()..];
+    if prefix.eq_ignore_ascii_case(keyword) && rest.starts_with(char::is_whitespace) {
+      Some(rest.trim())
+    } else {
+      None
+    }
+  })?;
+  let (title, anchor) = match target.find(ANCHOR) {
+    Some(index) => (&target[..index], Some(&target[index + ANCHOR.len_utf8()..])),
+    None => (target, None),
+  };
+  let title = title.trim_end();
+  if title.is_empty() {
+    return None;
+  }
+
+  Some(Redirect {
+    title: options.namespace_registry().title(title),
+    anchor: anchor
+      .filter(|anchor| !anchor.is_empty())
+      .map(Cow::Borrowed),
+  })
+}
+
+#[cfg(test)]
+mod tests {
+  use crate::*;
+
+  #[test]
+  fn redirect() {
+    assert_eq!(
+      parse_document("#redirect 나무위키"),
+      Document::Redirect(Redirect {
+        title: PageTitle::new(None, "나무위키"),
+        anchor: None
+      })
+    );
+    assert_eq!(
+      parse_document("#넘겨주기 틀:foo\n무시되는 내용"),
+      Document::Redirect(Redirect {
+        title: PageTitle::new(Some("틀"), "foo"),
+        anchor: None
+      })
+    );
+  }
+
+  #[test]
+  fn anchor() {
+    assert_eq!(
+      parse_document("#REDIRECT 문서#s-2"),
+      Document::Redirect(Redirect {
+        title: PageTitle::new(None, "문서"),
+        anchor: Some("s-2".into())
+      })
+    );
+    assert_eq!(
+      crate::redirect("#redirect 문서#"),
+      Some(Redirect {
+        title: PageTitle::new(None, "문서"),
+        anchor: None
+      })
+    );
+  }
+
+  #[test]
+  fn content() {
+    assert_eq!(
+      parse_document("foo\n#redirect bar"),
+      Document::Content(parse("foo\n#redirect bar"))
+    );
+    assert_eq!(crate::redirect("#redirectfoo"), None);
+    assert_eq!(crate::redirect("#redirect "), None);
+    assert_eq!(crate::redirect("#넘겨주기"), None);
+  }
+
+  #[test]
+  fn disabled() {
+    let options = ParseOptions::new().disable(Syntax::Redirect);
+    assert_eq!(
+      parse_document_with("#redirect foo", &options),
+      Document::Content(parse("#redirect foo"))
+    );
+  }
+}
@@ -0,0 +1,368 @@
+use crate::{
+  categories, links, parse_document_with, span::bracket_pair_list, Document, ParseOptions,
+};
+use rayon::prelude::*;
+use serde::{
+  de::{self, DeserializeSeed, SeqAccess, Visitor},
+  Deserialize, Deserializer, Serialize,
+};
+use std::{
+  error, fmt,
+  io::{self, BufReader, BufWriter, Read, Write},
+  panic::{self, AssertUnwindSafe},
+};
+
+/// 한 번에 병렬로 파싱하는 문서 수. 메모리에는 이만큼의 문서만 올라간다
+const BATCH_SIZE: usize = 256;
+
+/// 덤프 배열의 원소 하나
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct DumpRecord {
+  pub title: String,
+  pub text: String,
+  /// 덤프에 따라 숫자나 문자열로 들어 있다
+  #[serde(default, deserialize_with = "string_or_number")]
+  pub namespace: String,
+  #[serde(default)]
+  pub contributors: Vec<String>,
+}
+
+/// 문서 하나를 처리한 결과. JSON 한 줄로 출력된다
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct DocumentSummary {
+  pub title: String,
+  pub namespace: String,
+  pub redirect: bool,
+  /// 넘겨주기 문서라면 대상 문서
+  #[serde(skip_serializing_if = "Option::is_none")]
+  pub redirect_target: Option<String>,
+  pub links: Vec<String>,
+  pub categories: Vec<String>,
+  pub diagnostics: Vec<Diagnostic>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum Diagnostic {
+  /// 짝이 없는 `{{{`의 바이트 위치
+  UnclosedBracket { offset: usize },
+  /// 파서가 패닉을 일으켜 결과를 만들지 못했다
+  Panic { message: String },
+}
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct DumpStats {
+  pub documents: usize,
+  pub redirects: usize,
+  /// 진단이 하나 이상 나온 문서 수
+  pub diagnosed: usize,
+}
+
+#[derive(Debug)]
+pub enum DumpError {
+  Io(io::Error),
+  Json(serde_json::Error),
+}
+
+impl fmt::Display for DumpError {
+  fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+    match self {
+      DumpError::Io(error) => write!(f, "{}", error),
+      DumpError::Json(error) => write!(f, "{}", error),
+    }
+  }
+}
+
+impl error::Error for DumpError {
+  fn source(&self) -> Option<&(dyn error::Error + 'static)> {
+    match self {
+      DumpError::Io(error) => Some(error),
+      DumpError::Json(error) => Some(error),
+    }
+  }
+}
+
+impl From<io::Error> for DumpError {
+  fn from(source: io::Error) -> Self {
+    DumpError::Io(source)
+  }
+}
+
+impl From<serde_json::Error> for DumpError {
+  fn from(source: serde_json::Error) -> Self {
+    DumpError::Json(source)
+  }
+}
+
+/// 덤프를 읽으면서 문서를 병렬로 파싱하고, 결과를 입력 순서대로 JSON Lines로 쓴다.
+/// 덤프 전체를 메모리에 올리지 않는다
+pub fn process_dump<R: Read, W: Write>(
+  reader: R,
+  writer: W,
+  options: &ParseOptions,
+) -> Result<DumpStats, DumpError> {
+  let mut writer = BufWriter::new(writer);
+  let mut write_error = None;
+  let mut deserializer = serde_json::Deserializer::from_reader(BufReader::new(reader));
+  let seed = DumpSeed {
+    writer: &mut writer,
+    options,
+    write_error: &mut write_error,
+  };
+  let stats = match seed.deserialize(&mut deserializer) {
+    Ok(stats) => stats,
+    Err(error) => {
+      return Err(match write_error {
+        Some(write_error) => write_error.into(),
+        None => error.into(),
+      })
+    }
+  };
+  deserializer.end()?;
+  writer.flush()?;
+
+  Ok(stats)
+}
+
+/// 파서가 패닉을 일으키더라도 덤프 처리를 멈추지 않고 진단으로 남긴다
+pub fn summarize(record: &DumpRecord, options: &ParseOptions) -> DocumentSummary {
+  let mut summary = DocumentSummary {
+    title: record.title.clone(),
+    namespace: record.namespace.clone(),
+    redirect: false,
+    redirect_target: None,
+    links: vec![],
+    categories: vec![],
+    diagnostics: vec![],
+  };
+  let parsed = panic::catch_unwind(AssertUnwindSafe(|| {
+    match parse_document_with(&record.text, options) {
+      Document::Redirect(redirect) => (Some(redirect.title.to_string()), vec![], vec![]),
+      Document::Content(block_list) => (
+        None,
+        links(&block_list).into_iter().map(str::to_owned).collect(),
+        categories(&block_list)
+          .into_iter()
+          .map(|category| category.title.to_string())
+          .collect(),
+      ),
+    }
+  }));
+  match parsed {
+    Ok((redirect_target, link_list, category_list)) => {
+      summary.redirect = redirect_target.is_some();
+      summary.redirect_target = redirect_target;
+      summary.links = link_list;
+      summary.categories = category_list;
+    }
+    Err(payload) => summary.diagnostics.push(Diagnostic::Panic {
+      message: panic_message(payload.as_ref()),
+    }),
+  }
+  if !summary.redirect {
+    summary.diagnostics.extend(
+      bracket_pair_list(&record.text)
+        .into_iter()
+        .filter(|(_, end)| end.is_none())
+        .map(|(offset, _)| Diagnostic::UnclosedBracket { offset }),
+    );
+  }
+
+  summary
+}
+
+fn panic_message(payload: &(dyn std::any::Any + Send)) -> String {
+  if let Some(message) = payload.downcast_ref::<&str>() {
+    (*message).to_owned()
+  } else if let Some(message) = payload.downcast_ref::<String>() {
+    message.clone()
+  } else {
+    "unknown panic".to_owned()
+  }
+}
+
+fn string_or_number<'de, D: Deserializer<'de>>(deserializer: D) -> Result<String, D::Error> {
+  #[derive(Deserialize)]
+  #[serde(untagged)]
+  enum StringOrNumber {
+    String(String),
+    Number(i64),
+  }
+
+  Ok(match StringOrNumber::deserialize(deserializer)? {
+    StringOrNumber::String(value) => value,
+    StringOrNumber::Number(value) => value.to_string(),
+  })
+}
+
+/// 최상위 배열을 원소 단위로 읽는다
+struct DumpSeed<'w, 'o, W: Write> {
+  writer: &'w mut W,
+  options: &'o ParseOptions,
+  /// 쓰기 실패는 serde 오류로만 전달할 수 있으므로 원래 오류를 따로 보관한다
+  write_error: &'w mut Option<io::Error>,
+}
+
+impl<'w, 'o, W: Write> DumpSeed<'w, 'o, W> {
+  fn flush(&mut self, batch: &mut Vec<DumpRecord>, stats: &mut DumpStats) -> io::Result<()> {
+    let options = self.options;
+    let summary_list: Vec<DocumentSummary> = batch
+      .par_iter()
+      .map(|record| summarize(record, options))
+      .collect();
+    batch.clear();
+    for summary in summary_list {
+      stats.documents += 1;
+      if summary.redirect {
+        stats.redirects += 1;
+      }
+      if !summary.diagnostics.is_empty() {
+        stats.diagnosed += 1;
+      }
+      serde_json::to_writer(&mut *self.writer, &summary)?;
+      self.writer.write_all(b"\n")?;
+    }
+
+    Ok(())
+  }
+}
+
+impl<'de, 'w, 'o, W: Write> DeserializeSeed<'de> for DumpSeed<'w, 'o, W> {
+  type Value = DumpStats;
+
+  fn deserialize<D: Deserializer<'de>>(self, deserializer: D) -> Result<Self::Value, D::Error> {
+    deserializer.deserialize_seq(self)
+  }
+}
+
+impl<'de, 'w, 'o, W: Write> Visitor<'de> for DumpSeed<'w, 'o, W> {
+  type Value = DumpStats;
+
+  fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+    write!(f, "an array of documents")
+  }
+
+  fn visit_seq<A: SeqAccess<'de>>(mut self, mut seq: A) -> Result<Self::Value, A::Error> {
+    let mut stats = DumpStats::default();
+    let mut batch = Vec::with_capacity(BATCH_SIZE);
+    loop {
+      let record = seq.next_element::<DumpRecord>()?;
+      let is_end = record.is_none();
+      batch.extend(record);
+      if batch.len() == BATCH_SIZE || (is_end && !batch.is_empty()) {
+        if let Err(error) = self.flush(&mut batch, &mut stats) {
+          let message = error.to_string();
+          *self.write_error = Some(error);
+          return Err(de::Error::custom(message));
+        }
+      }
+      if is_end {
+        return Ok(stats);
+      }
+    }
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use crate::*;
+
+  const FIXTURE: &str = include_str!("../fixtures/dump.json");
+
+  fn process(source: &str) -> (DumpStats, Vec<serde_json::Value>) {
+    let mut output = vec![];
+    let stats = process_dump(source.as_bytes(), &mut output, &Default::default()).unwrap();
+    let line_list = String::from_utf8(output)
+      .unwrap()
+      .lines()
+      .map(|line| serde_json::from_str(line).unwrap())
+      .collect();
+
+    (stats, line_list)
+  }
+
+  #[test]
+  fn fixture() {
+    let (stats, line_list) = process(FIXTURE);
+    assert_eq!(
+      stats,
+      DumpStats {
+        documents: 3,
+        redirects: 1,
+        diagnosed: 1
+      }
+    );
+    assert_eq!(
+      line_list,
+      vec![
+        serde_json::json!({
+          "title": "나무위키",
+          "namespace": "0",
+          "redirect": false,
+          "links": ["위키", "틀:문서"],
+          "categories": ["분류:위키"],
+          "diagnostics": []
+        }),
+        serde_json::json!({
+          "title": "나무",
+          "namespace": "0",
+          "redirect": true,
+          "redirect_target": "나무위키",
+          "links": [],
+          "categories": [],
+          "diagnostics": []
+        }),
+        serde_json::json!({
+          "title": "틀:문서",
+          "namespace": "1",
+          "redirect": false,
+          "links": [],
+          "categories": [],
+          "diagnostics": [{ "kind": "unclosed_bracket", "offset": 4 }]
+        }),
+      ]
+    );
+  }
+
+  #[test]
+  fn keeps_order_across_batches() {
+    let record_list: Vec<String> = (0..1000)
+      .map(|index| format!(r#"{{"title":"{}","text":"[[{}]]"}}"#, index, index))
+      .collect();
+    let (stats, line_list) = process(&format!("[{}]", record_list.join(",")));
+    assert_eq!(stats.documents, 1000);
+    for (index, line) in line_list.iter().enumerate() {
+      assert_eq!(line["title"], index.to_string());
+      assert_eq!(line["links"][0], index.to_string());
+    }
+  }
+
+  #[test]
+  fn invalid_json() {
+    let mut output = vec![];
+    let result = process_dump(&b"[{\"title\": 1}]"[..], &mut output, &Default::default());
+    assert!(matches!(result, Err(DumpError::Json(_))));
+    assert!(matches!(
+      process_dump(&b"[] []"[..], &mut output, &Default::default()),
+      Err(DumpError::Json(_))
+    ));
+  }
+
+  #[test]
+  fn write_error() {
+    struct Broken;
+
+    impl std::io::Write for Broken {
+      fn write(&mut self, _: &[u8]) -> std::io::Result<usize> {
+        Err(std::io::Error::new(std::io::ErrorKind::Other, "broken"))
+      }
+
+      fn flush(&mut self) -> std::io::Result<()> {
+        Ok(())
+      }
+    }
+
+    let result = process_dump(FIXTURE.as_bytes(), Broken, &Default::default());
+    assert!(matches!(result, Err(DumpError::Io(_))));
+  }
+}
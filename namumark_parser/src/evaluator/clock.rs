@@ -0,0 +1,34 @@
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// 현재 시각을 유닉스 타임스탬프(초)로 돌려준다
+pub trait Clock {
+  fn now(&self) -> i64;
+}
+
+#[derive(Debug, Default, Clone, Copy)]
+pub struct SystemClock;
+
+/// 항상 같은 시각을 돌려주는 시계. 테스트처럼 결과가 고정되어야 할 때 사용한다
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct FixedClock(pub i64);
+
+impl Clock for SystemClock {
+  fn now(&self) -> i64 {
+    match SystemTime::now().duration_since(UNIX_EPOCH) {
+      Ok(duration) => duration.as_secs() as i64,
+      Err(error) => -(error.duration().as_secs() as i64),
+    }
+  }
+}
+
+impl Clock for FixedClock {
+  fn now(&self) -> i64 {
+    self.0
+  }
+}
+
+impl<T: Clock + ?Sized> Clock for &T {
+  fn now(&self) -> i64 {
+    (**self).now()
+  }
+}
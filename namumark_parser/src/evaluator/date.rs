@@ -0,0 +1,282 @@
+use std::{fmt, str::FromStr};
+
+const SECONDS_PER_DAY: i64 = 86_400;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub struct Date {
+  year: i32,
+  month: u32,
+  day: u32,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct DateTime {
+  date: Date,
+  hour: u32,
+  minute: u32,
+  second: u32,
+  offset: UtcOffset,
+}
+
+/// UTC 기준 시차(초 단위)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct UtcOffset(i32);
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum DateError {
+  InvalidFormat,
+  InvalidMonth(u32),
+  InvalidDay(u32),
+}
+
+impl Date {
+  pub fn new(year: i32, month: u32, day: u32) -> Result<Self, DateError> {
+    if !(1..=12).contains(&month) {
+      return Err(DateError::InvalidMonth(month));
+    }
+    if !(1..=days_in_month(year, month)).contains(&day) {
+      return Err(DateError::InvalidDay(day));
+    }
+
+    Ok(Date { year, month, day })
+  }
+
+  pub fn year(&self) -> i32 {
+    self.year
+  }
+
+  pub fn month(&self) -> u32 {
+    self.month
+  }
+
+  pub fn day(&self) -> u32 {
+    self.day
+  }
+
+  /// 1970-01-01부터 지난 일 수
+  pub fn days_since_epoch(&self) -> i64 {
+    let year = i64::from(self.year) - if self.month <= 2 { 1 } else { 0 };
+    let era = if year >= 0 { year } else { year - 399 } / 400;
+    let year_of_era = year - era * 400;
+    let month = i64::from(self.month);
+    let day_of_year =
+      (153 * (month + if month > 2 { -3 } else { 9 }) + 2) / 5 + i64::from(self.day) - 1;
+    let day_of_era = year_of_era * 365 + year_of_era / 4 - year_of_era / 100 + day_of_year;
+
+    era * 146_097 + day_of_era - 719_468
+  }
+
+  pub fn from_days_since_epoch(days: i64) -> Self {
+    let days = days + 719_468;
+    let era = if days >= 0 { days } else { days - 146_096 } / 146_097;
+    let day_of_era = days - era * 146_097;
+    let year_of_era =
+      (day_of_era - day_of_era / 1460 + day_of_era / 36524 - day_of_era / 146_096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let month_prime = (5 * day_of_year + 2) / 153;
+    let day = day_of_year - (153 * month_prime + 2) / 5 + 1;
+    let month = if month_prime < 10 {
+      month_prime + 3
+    } else {
+      month_prime - 9
+    };
+    let year = year_of_era + era * 400 + if month <= 2 { 1 } else { 0 };
+
+    Date {
+      year: year as i32,
+      month: month as u32,
+      day: day as u32,
+    }
+  }
+}
+
+impl FromStr for Date {
+  type Err = DateError;
+
+  /// `YYYY-MM-DD` 형식만 허용한다
+  fn from_str(source: &str) -> Result<Self, Self::Err> {
+    fn number<T: FromStr>(source: &str, length: usize) -> Result<T, DateError> {
+      if source.len() == length && source.bytes().all(|byte| byte.is_ascii_digit()) {
+        source.parse().map_err(|_| DateError::InvalidFormat)
+      } else {
+        Err(DateError::InvalidFormat)
+      }
+    }
+
+    let token_list: Vec<&str> = source.trim().split('-').collect();
+    match token_list[..] {
+      [year, month, day] => Date::new(number(year, 4)?, number(month, 2)?, number(day, 2)?),
+      _ => Err(DateError::InvalidFormat),
+    }
+  }
+}
+
+impl fmt::Display for Date {
+  fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+    write!(f, "{:04}-{:02}-{:02}", self.year, self.month, self.day)
+  }
+}
+
+impl DateTime {
+  pub fn from_timestamp(timestamp: i64, offset: UtcOffset) -> Self {
+    let local = timestamp + i64::from(offset.0);
+    let days = local.div_euclid(SECONDS_PER_DAY);
+    let seconds = local.rem_euclid(SECONDS_PER_DAY) as u32;
+
+    DateTime {
+      date: Date::from_days_since_epoch(days),
+      hour: seconds / 3600,
+      minute: seconds % 3600 / 60,
+      second: seconds % 60,
+      offset,
+    }
+  }
+
+  pub fn date(&self) -> Date {
+    self.date
+  }
+
+  pub fn hour(&self) -> u32 {
+    self.hour
+  }
+
+  pub fn minute(&self) -> u32 {
+    self.minute
+  }
+
+  pub fn second(&self) -> u32 {
+    self.second
+  }
+
+  pub fn offset(&self) -> UtcOffset {
+    self.offset
+  }
+}
+
+impl fmt::Display for DateTime {
+  fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+    write!(
+      f,
+      "{} {:02}:{:02}:{:02}{}",
+      self.date, self.hour, self.minute, self.second, self.offset
+    )
+  }
+}
+
+impl UtcOffset {
+  pub const UTC: UtcOffset = UtcOffset(0);
+  /// 나무위키가 사용하는 한국 표준시
+  pub const KST: UtcOffset = UtcOffset(9 * 3600);
+
+  pub fn seconds(seconds: i32) -> Self {
+    UtcOffset(seconds)
+  }
+
+  pub fn hours(hours: i32) -> Self {
+    UtcOffset(hours * 3600)
+  }
+
+  pub fn as_seconds(&self) -> i32 {
+    self.0
+  }
+}
+
+impl Default for UtcOffset {
+  fn default() -> Self {
+    Self::KST
+  }
+}
+
+impl fmt::Display for UtcOffset {
+  fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+    let sign = if self.0 < 0 { '-' } else { '+' };
+    let minutes = self.0.abs() / 60;
+
+    write!(f, "{}{:02}{:02}", sign, minutes / 60, minutes % 60)
+  }
+}
+
+impl fmt::Display for DateError {
+  fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+    match self {
+      DateError::InvalidFormat => write!(f, "date must be in YYYY-MM-DD format"),
+      DateError::InvalidMonth(month) => write!(f, "month {} does not exist", month),
+      DateError::InvalidDay(day) => write!(f, "day {} does not exist in the month", day),
+    }
+  }
+}
+
+impl std::error::Error for DateError {}
+
+fn is_leap_year(year: i32) -> bool {
+  (year % 4 == 0 && year % 100 != 0) || year % 400 == 0
+}
+
+fn days_in_month(year: i32, month: u32) -> u32 {
+  match month {
+    2 if is_leap_year(year) => 29,
+    2 => 28,
+    4 | 6 | 9 | 11 => 30,
+    _ => 31,
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use crate::*;
+
+  #[test]
+  fn parse() {
+    assert_eq!("2020-01-31".parse(), Date::new(2020, 1, 31));
+    assert_eq!("2020-02-29".parse(), Date::new(2020, 2, 29));
+  }
+
+  #[test]
+  fn invalid_format() {
+    assert_eq!("2020-1-1".parse::<Date>(), Err(DateError::InvalidFormat));
+    assert_eq!("2020/01/01".parse::<Date>(), Err(DateError::InvalidFormat));
+    assert_eq!("+020-01-01".parse::<Date>(), Err(DateError::InvalidFormat));
+    assert_eq!("".parse::<Date>(), Err(DateError::InvalidFormat));
+  }
+
+  #[test]
+  fn impossible_date() {
+    assert_eq!(
+      "2020-13-01".parse::<Date>(),
+      Err(DateError::InvalidMonth(13))
+    );
+    assert_eq!(
+      "2020-00-01".parse::<Date>(),
+      Err(DateError::InvalidMonth(0))
+    );
+    assert_eq!("2019-02-29".parse::<Date>(), Err(DateError::InvalidDay(29)));
+    assert_eq!("1900-02-29".parse::<Date>(), Err(DateError::InvalidDay(29)));
+    assert_eq!("2020-04-31".parse::<Date>(), Err(DateError::InvalidDay(31)));
+  }
+
+  #[test]
+  fn days_since_epoch() {
+    let epoch = Date::new(1970, 1, 1).unwrap();
+    assert_eq!(epoch.days_since_epoch(), 0);
+
+    let date = Date::new(2000, 3, 1).unwrap();
+    assert_eq!(date.days_since_epoch(), 11017);
+    assert_eq!(Date::from_days_since_epoch(11017), date);
+
+    let date = Date::new(1969, 12, 31).unwrap();
+    assert_eq!(date.days_since_epoch(), -1);
+    assert_eq!(Date::from_days_since_epoch(-1), date);
+  }
+
+  #[test]
+  fn datetime() {
+    let datetime = DateTime::from_timestamp(1_577_804_400, UtcOffset::KST);
+    assert_eq!(datetime.to_string(), "2020-01-01 00:00:00+0900");
+
+    let datetime = DateTime::from_timestamp(1_577_804_400, UtcOffset::UTC);
+    assert_eq!(datetime.to_string(), "2019-12-31 15:00:00+0000");
+
+    let datetime = DateTime::from_timestamp(0, UtcOffset::seconds(-(3 * 3600 + 30 * 60)));
+    assert_eq!(datetime.to_string(), "1969-12-31 20:30:00-0330");
+  }
+}
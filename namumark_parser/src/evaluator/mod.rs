@@ -0,0 +1,296 @@
+mod clock;
+mod date;
+mod stats;
+
+use crate::{MacroSpan, NamespaceRegistry};
+pub use clock::{Clock, FixedClock, SystemClock};
+pub use date::{Date, DateError, DateTime, UtcOffset};
+pub use stats::{FixtureStats, WikiStats};
+use std::fmt;
+
+/// 렌더링 시점에 값이 정해지는 매크로를 문자열로 평가한다
+pub trait MacroEvaluator {
+  /// 평가 대상이 아닌 매크로는 `None`을 돌려준다
+  fn evaluate(&self, span: &MacroSpan) -> Option<Result<String, EvaluateError>>;
+}
+
+/// 시계와 통계는 빌려 올 수 있고, 스레드 사이에서 평가기를 나눠 쓸 수 있도록 `Send + Sync`여야 한다
+pub struct Evaluator<'a> {
+  clock: Box<dyn Clock + Send + Sync + 'a>,
+  timezone: UtcOffset,
+  stats: Option<Box<dyn WikiStats + Send + Sync + 'a>>,
+  namespaces: NamespaceRegistry,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum EvaluateError {
+  InvalidDate(DateError),
+  FutureDate(Date),
+  UnknownNamespace(String),
+  MissingStats,
+}
+
+impl<'a> Evaluator<'a> {
+  pub fn new<C: Clock + Send + Sync + 'a>(clock: C) -> Self {
+    Evaluator {
+      clock: Box::new(clock),
+      timezone: Default::default(),
+      stats: None,
+      namespaces: Default::default(),
+    }
+  }
+
+  pub fn timezone(mut self, timezone: UtcOffset) -> Self {
+    self.timezone = timezone;
+    self
+  }
+
+  pub fn stats<S: WikiStats + Send + Sync + 'a>(mut self, stats: S) -> Self {
+    self.stats = Some(Box::new(stats));
+    self
+  }
+
+  pub fn namespaces(mut self, namespaces: NamespaceRegistry) -> Self {
+    self.namespaces = namespaces;
+    self
+  }
+
+  pub fn now(&self) -> DateTime {
+    DateTime::from_timestamp(self.clock.now(), self.timezone)
+  }
+
+  pub fn today(&self) -> Date {
+    self.now().date()
+  }
+
+  /// 만 나이. 생일이 오지 않았다면 한 살을 빼며, 2월 29일생은 평년에 3월 1일부터 나이를 먹는다
+  pub fn age(&self, birth: &str) -> Result<String, EvaluateError> {
+    let birth: Date = birth.parse()?;
+    let today = self.today();
+    if birth > today {
+      return Err(EvaluateError::FutureDate(birth));
+    }
+
+    let mut age = today.year() - birth.year();
+    if (today.month(), today.day()) < (birth.month(), birth.day()) {
+      age -= 1;
+    }
+
+    Ok(age.to_string())
+  }
+
+  /// 지난 날짜는 `+N`, 당일은 `+0`, 다가올 날짜는 `-N`
+  pub fn dday(&self, target: &str) -> Result<String, EvaluateError> {
+    let target: Date = target.parse()?;
+    let days = self.today().days_since_epoch() - target.days_since_epoch();
+
+    if days >= 0 {
+      Ok(format!("+{}", days))
+    } else {
+      Ok(days.to_string())
+    }
+  }
+
+  /// 나무위키는 `[date]`와 `[datetime]`을 같은 형식으로 출력한다
+  pub fn datetime(&self) -> String {
+    self.now().to_string()
+  }
+
+  /// 이름공간이 없으면 전체 문서 수를 센다
+  pub fn page_count(&self, namespace: Option<&str>) -> Result<String, EvaluateError> {
+    let stats = self.stats.as_ref().ok_or(EvaluateError::MissingStats)?;
+    let count = match namespace.map(str::trim) {
+      None => stats.total_pages(),
+      Some(namespace) => match self.namespaces.resolve(namespace) {
+        Some(namespace) => stats.pages_in(namespace),
+        None => return Err(EvaluateError::UnknownNamespace(namespace.to_owned())),
+      },
+    };
+
+    Ok(count.to_string())
+  }
+}
+
+impl Default for Evaluator<'_> {
+  fn default() -> Self {
+    Evaluator::new(SystemClock)
+  }
+}
+
+impl MacroEvaluator for Evaluator<'_> {
+  fn evaluate(&self, span: &MacroSpan) -> Option<Result<String, EvaluateError>> {
+    match span {
+      MacroSpan::Age(birth) => Some(self.age(birth)),
+      MacroSpan::Dday(target) => Some(self.dday(target)),
+      MacroSpan::Date | MacroSpan::Datetime => Some(Ok(self.datetime())),
+      MacroSpan::PageCount(namespace) => Some(self.page_count(namespace.as_deref())),
+      _ => None,
+    }
+  }
+}
+
+impl From<DateError> for EvaluateError {
+  fn from(error: DateError) -> Self {
+    EvaluateError::InvalidDate(error)
+  }
+}
+
+impl fmt::Display for EvaluateError {
+  fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+    match self {
+      EvaluateError::InvalidDate(error) => write!(f, "invalid date: {}", error),
+      EvaluateError::FutureDate(date) => write!(f, "{} is in the future", date),
+      EvaluateError::UnknownNamespace(namespace) => write!(f, "unknown namespace {}", namespace),
+      EvaluateError::MissingStats => write!(f, "no wiki statistics provider is configured"),
+    }
+  }
+}
+
+impl std::error::Error for EvaluateError {
+  fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+    match self {
+      EvaluateError::InvalidDate(error) => Some(error),
+      _ => None,
+    }
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use crate::*;
+
+  // 2020-03-01 00:00:00+0900
+  const MARCH_FIRST: i64 = 1_582_988_400;
+
+  fn evaluator() -> Evaluator<'static> {
+    Evaluator::new(FixedClock(MARCH_FIRST))
+  }
+
+  #[test]
+  fn age() {
+    let evaluator = evaluator();
+    assert_eq!(evaluator.age("2000-03-01"), Ok("20".to_owned()));
+    assert_eq!(evaluator.age("2000-03-02"), Ok("19".to_owned()));
+    assert_eq!(evaluator.age("2020-03-01"), Ok("0".to_owned()));
+  }
+
+  #[test]
+  fn age_of_leap_day() {
+    let evaluator = Evaluator::new(FixedClock(MARCH_FIRST - 86_400 * 367));
+    assert_eq!(evaluator.today(), Date::new(2019, 2, 28).unwrap());
+    assert_eq!(evaluator.age("2016-02-29"), Ok("2".to_owned()));
+
+    let evaluator = Evaluator::new(FixedClock(MARCH_FIRST - 86_400 * 366));
+    assert_eq!(evaluator.today(), Date::new(2019, 3, 1).unwrap());
+    assert_eq!(evaluator.age("2016-02-29"), Ok("3".to_owned()));
+  }
+
+  #[test]
+  fn age_of_future() {
+    assert_eq!(
+      evaluator().age("2020-03-02"),
+      Err(EvaluateError::FutureDate(Date::new(2020, 3, 2).unwrap()))
+    );
+  }
+
+  #[test]
+  fn age_of_invalid_date() {
+    assert_eq!(
+      evaluator().age("2019-02-29"),
+      Err(EvaluateError::InvalidDate(DateError::InvalidDay(29)))
+    );
+  }
+
+  #[test]
+  fn dday() {
+    let evaluator = evaluator();
+    assert_eq!(evaluator.dday("2020-02-01"), Ok("+29".to_owned()));
+    assert_eq!(evaluator.dday("2020-03-01"), Ok("+0".to_owned()));
+    assert_eq!(evaluator.dday("2020-03-11"), Ok("-10".to_owned()));
+  }
+
+  #[test]
+  fn timezone() {
+    let evaluator = evaluator().timezone(UtcOffset::UTC);
+    assert_eq!(evaluator.today(), Date::new(2020, 2, 29).unwrap());
+    assert_eq!(evaluator.dday("2020-03-01"), Ok("-1".to_owned()));
+    assert_eq!(evaluator.datetime(), "2020-02-29 15:00:00+0000");
+  }
+
+  #[test]
+  fn evaluate() {
+    let evaluator = evaluator();
+    assert_eq!(
+      evaluator.evaluate(&MacroSpan::Age("2000-01-01".into())),
+      Some(Ok("20".to_owned()))
+    );
+    assert_eq!(
+      evaluator.evaluate(&MacroSpan::Dday("2020-02-29".into())),
+      Some(Ok("+1".to_owned()))
+    );
+    assert_eq!(
+      evaluator.evaluate(&MacroSpan::Date),
+      Some(Ok("2020-03-01 00:00:00+0900".to_owned()))
+    );
+    assert_eq!(
+      evaluator.evaluate(&MacroSpan::Datetime),
+      Some(Ok("2020-03-01 00:00:00+0900".to_owned()))
+    );
+    assert_eq!(evaluator.evaluate(&MacroSpan::Footnote), None);
+  }
+
+  #[test]
+  fn page_count() {
+    let evaluator = evaluator().stats(FixtureStats::new().namespace("문서", 10).namespace("틀", 3));
+    assert_eq!(
+      evaluator.evaluate(&MacroSpan::PageCount(None)),
+      Some(Ok("13".to_owned()))
+    );
+    assert_eq!(
+      evaluator.evaluate(&MacroSpan::PageCount(Some("문서".into()))),
+      Some(Ok("10".to_owned()))
+    );
+    assert_eq!(
+      evaluator.evaluate(&MacroSpan::PageCount(Some("파일".into()))),
+      Some(Ok("0".to_owned()))
+    );
+  }
+
+  #[test]
+  fn page_count_of_unknown_namespace() {
+    let evaluator = evaluator().stats(FixtureStats::new());
+    assert_eq!(
+      evaluator.page_count(Some("file")),
+      Err(EvaluateError::UnknownNamespace("file".to_owned()))
+    );
+
+    let evaluator = evaluator.namespaces(NamespaceRegistry::new().register("file"));
+    assert_eq!(evaluator.page_count(Some("file")), Ok("0".to_owned()));
+  }
+
+  #[test]
+  fn page_count_of_alias() {
+    let evaluator = evaluator().stats(FixtureStats::new().namespace("틀", 3));
+    assert_eq!(evaluator.page_count(Some("Template")), Ok("3".to_owned()));
+  }
+
+  #[test]
+  fn page_count_without_stats() {
+    assert_eq!(
+      evaluator().page_count(None),
+      Err(EvaluateError::MissingStats)
+    );
+  }
+
+  #[test]
+  fn borrowed() {
+    fn assert_send_sync<T: Send + Sync>(_: &T) {}
+
+    let clock = FixedClock(MARCH_FIRST);
+    let stats = FixtureStats::new().namespace("문서", 3);
+    let evaluator = Evaluator::new(&clock).stats(&stats);
+    assert_send_sync(&evaluator);
+    assert_eq!(evaluator.age("2000-03-01"), Ok("20".to_owned()));
+    assert_eq!(evaluator.page_count(None), Ok("3".to_owned()));
+  }
+}
@@ -0,0 +1,58 @@
+use std::collections::HashMap;
+
+/// `[pagecount]` 매크로가 참조하는 위키 통계
+pub trait WikiStats {
+  fn total_pages(&self) -> u64;
+
+  fn pages_in(&self, namespace: &str) -> u64;
+}
+
+/// 미리 정해둔 숫자를 돌려주는 통계. 테스트용으로 사용한다
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct FixtureStats {
+  namespace_map: HashMap<String, u64>,
+}
+
+impl FixtureStats {
+  pub fn new() -> Self {
+    Default::default()
+  }
+
+  pub fn namespace(mut self, namespace: &str, pages: u64) -> Self {
+    self.namespace_map.insert(namespace.to_owned(), pages);
+    self
+  }
+}
+
+impl WikiStats for FixtureStats {
+  fn total_pages(&self) -> u64 {
+    self.namespace_map.values().sum()
+  }
+
+  fn pages_in(&self, namespace: &str) -> u64 {
+    self.namespace_map.get(namespace).copied().unwrap_or(0)
+  }
+}
+
+impl<T: WikiStats + ?Sized> WikiStats for &T {
+  fn total_pages(&self) -> u64 {
+    (**self).total_pages()
+  }
+
+  fn pages_in(&self, namespace: &str) -> u64 {
+    (**self).pages_in(namespace)
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use crate::*;
+
+  #[test]
+  fn fixture() {
+    let stats = FixtureStats::new().namespace("문서", 10).namespace("틀", 3);
+    assert_eq!(stats.total_pages(), 13);
+    assert_eq!(stats.pages_in("문서"), 10);
+    assert_eq!(stats.pages_in("파일"), 0);
+  }
+}
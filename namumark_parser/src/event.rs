@@ -0,0 +1,324 @@
+use crate::{
+  block::block, Block, BracketSpan, Category, Color, CommandSpan, Context, FontSizeLevel,
+  HeadingLevel, ImageOption, ListIndex, ListItem, MacroSpan, MultilineBlock, PageTitle,
+  ParseOptions, SemanticSpan, SinglelineBlock, Span, VideoOption,
+};
+use std::{borrow::Cow, collections::VecDeque};
+
+/// `Parser`가 문서 순서대로 내놓는 사건. 자식이 있는 노드는 `Start`와 `End` 사이에 자식을 내놓는다
+#[derive(Debug, PartialEq)]
+pub enum Event<'a> {
+  Start(Tag<'a>),
+  End(Tag<'a>),
+  Text(Cow<'a, str>),
+  /// `{{{문법 없이 그대로}}}`
+  Code(Cow<'a, str>),
+  /// `##주석`
+  Comment(Cow<'a, str>),
+  /// `\`로 이스케이프된 글자
+  Escaped(char),
+  Linebreak,
+  HorizontalRule,
+  /// 각주를 뺀 매크로
+  Macro(MacroSpan<'a>),
+  Category(Category<'a>),
+  Image(PageTitle<'a>, ImageOption<'a>),
+  Video(Cow<'a, str>, VideoOption),
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Tag<'a> {
+  OpenHeading(HeadingLevel),
+  /// 처음에 접혀 있는 문단
+  ClosedHeading(HeadingLevel),
+  Paragraph,
+  Blockquote,
+  Indent,
+  /// 시작 번호와 중첩 깊이
+  OrderedList(ListIndex, usize),
+  /// 중첩 깊이
+  UnorderedList(usize),
+  ListItem,
+  Delete,
+  Emphasis,
+  Strong,
+  Subscript,
+  Superscript,
+  Underline,
+  Color(Color<'a>),
+  SizeDown(FontSizeLevel),
+  SizeUp(FontSizeLevel),
+  Folding,
+  /// 언어 이름. 코드는 `Text`로 나온다
+  SyntaxHighlight(Cow<'a, str>),
+  /// 대상과 문단 이름
+  Link(Cow<'a, str>, Option<Cow<'a, str>>),
+  /// 각주 이름. 이름이 없으면 빈 문자열이다
+  Footnote(Cow<'a, str>),
+}
+
+/// 블록 트리를 사건으로 바꾸는 어댑터. 최상위 블록을 하나씩 트리로 파싱한 뒤 사건으로 풀어 내놓는다.
+/// 문서 전체의 트리를 한꺼번에 들고 있지는 않지만, 최상위 블록 하나의 트리는 그대로 만든다.
+/// 그래서 긴 목록이나 `{{{#!folding}}}`처럼 큰 블록 하나는 `parse`만큼 메모리를 쓴다
+pub struct Parser<'a, 'o> {
+  /// 아직 파싱하지 않은 입력. 더 파싱할 블록이 없으면 `None`
+  input: Option<&'a str>,
+  options: Cow<'o, ParseOptions>,
+  event_queue: VecDeque<Event<'a>>,
+}
+
+impl<'a> Parser<'a, 'static> {
+  pub fn new(input: &'a str) -> Self {
+    Parser {
+      input: Some(input),
+      options: Cow::Owned(Default::default()),
+      event_queue: VecDeque::new(),
+    }
+  }
+}
+
+impl<'a, 'o> Parser<'a, 'o> {
+  pub fn new_with(input: &'a str, options: &'o ParseOptions) -> Self {
+    Parser {
+      input: Some(input),
+      options: Cow::Borrowed(options),
+      event_queue: VecDeque::new(),
+    }
+  }
+}
+
+impl<'a, 'o> Iterator for Parser<'a, 'o> {
+  type Item = Event<'a>;
+
+  fn next(&mut self) -> Option<Self::Item> {
+    // `block_list`와 같은 조건으로 멈춘다
+    while self.event_queue.is_empty() {
+      let input = self.input.take()?;
+      if let Ok((next_input, block)) = block(input, Context::new(&self.options)) {
+        block_events(block, &mut self.event_queue);
+        if !next_input.is_empty() {
+          self.input = Some(next_input);
+        }
+      }
+    }
+
+    self.event_queue.pop_front()
+  }
+}
+
+fn block_events<'a>(block: Block<'a>, event_queue: &mut VecDeque<Event<'a>>) {
+  match block {
+    Block::Singleline(SinglelineBlock::OpenHeading(span_list, level)) => {
+      wrap(Tag::OpenHeading(level), event_queue, |event_queue| {
+        span_list_events(span_list, event_queue)
+      })
+    }
+    Block::Singleline(SinglelineBlock::ClosedHeading(span_list, level)) => {
+      wrap(Tag::ClosedHeading(level), event_queue, |event_queue| {
+        span_list_events(span_list, event_queue)
+      })
+    }
+    Block::Singleline(SinglelineBlock::Comment(comment)) => {
+      event_queue.push_back(Event::Comment(comment))
+    }
+    Block::Multiline(block) => multiline_block_events(block, event_queue),
+  }
+}
+
+fn multiline_block_events<'a>(block: MultilineBlock<'a>, event_queue: &mut VecDeque<Event<'a>>) {
+  let (tag, block_list) = match block {
+    MultilineBlock::Blockquote(block_list) => (Tag::Blockquote, block_list),
+    MultilineBlock::Indent(block_list) => (Tag::Indent, block_list),
+    MultilineBlock::OrderedList(item_list, index, depth) => {
+      return wrap(Tag::OrderedList(index, depth), event_queue, |event_queue| {
+        list_item_events(item_list, event_queue)
+      })
+    }
+    MultilineBlock::UnorderedList(item_list, depth) => {
+      return wrap(Tag::UnorderedList(depth), event_queue, |event_queue| {
+        list_item_events(item_list, event_queue)
+      })
+    }
+    MultilineBlock::Paragraph(span_list) => {
+      return wrap(Tag::Paragraph, event_queue, |event_queue| {
+        span_list_events(span_list, event_queue)
+      })
+    }
+    MultilineBlock::HorizontalRule => return event_queue.push_back(Event::HorizontalRule),
+  };
+  wrap(tag, event_queue, |event_queue| {
+    for block in block_list {
+      multiline_block_events(block, event_queue);
+    }
+  })
+}
+
+fn list_item_events<'a>(item_list: Vec<ListItem<'a>>, event_queue: &mut VecDeque<Event<'a>>) {
+  for item in item_list {
+    wrap(Tag::ListItem, event_queue, |event_queue| {
+      for block in item {
+        multiline_block_events(block, event_queue);
+      }
+    });
+  }
+}
+
+fn span_list_events<'a>(span_list: Vec<Span<'a>>, event_queue: &mut VecDeque<Event<'a>>) {
+  for span in span_list {
+    span_events(span, event_queue);
+  }
+}
+
+fn span_events<'a>(span: Span<'a>, event_queue: &mut VecDeque<Event<'a>>) {
+  let (tag, span_list) = match span {
+    Span::Semantic(SemanticSpan::Delete(span_list)) => (Tag::Delete, span_list),
+    Span::Semantic(SemanticSpan::Emphasis(span_list)) => (Tag::Emphasis, span_list),
+    Span::Semantic(SemanticSpan::Strong(span_list)) => (Tag::Strong, span_list),
+    Span::Semantic(SemanticSpan::Subscript(span_list)) => (Tag::Subscript, span_list),
+    Span::Semantic(SemanticSpan::Superscript(span_list)) => (Tag::Superscript, span_list),
+    Span::Semantic(SemanticSpan::Underline(span_list)) => (Tag::Underline, span_list),
+    Span::Semantic(SemanticSpan::Linebreak) => return event_queue.push_back(Event::Linebreak),
+    Span::Bracket(BracketSpan::Color(span_list, color)) => (Tag::Color(color), span_list),
+    Span::Bracket(BracketSpan::SizeDown(span_list, level)) => (Tag::SizeDown(level), span_list),
+    Span::Bracket(BracketSpan::SizeUp(span_list, level)) => (Tag::SizeUp(level), span_list),
+    Span::Bracket(BracketSpan::Folding(block_list)) => {
+      return wrap(Tag::Folding, event_queue, |event_queue| {
+        for block in block_list {
+          multiline_block_events(block, event_queue);
+        }
+      })
+    }
+    Span::Bracket(BracketSpan::Inline(code)) => return event_queue.push_back(Event::Code(code)),
+    Span::Bracket(BracketSpan::SyntaxHighlight(code, language)) => {
+      return wrap(Tag::SyntaxHighlight(language), event_queue, |event_queue| {
+        event_queue.push_back(Event::Text(code))
+      })
+    }
+    Span::Macro(MacroSpan::Comment(span_list, name)) => (Tag::Footnote(name), span_list),
+    Span::Macro(span) => return event_queue.push_back(Event::Macro(span)),
+    Span::Command(CommandSpan::Link(span_list, link, anchor)) => {
+      (Tag::Link(link, anchor), span_list)
+    }
+    Span::Command(CommandSpan::Category(category)) => {
+      return event_queue.push_back(Event::Category(category))
+    }
+    Span::Command(CommandSpan::Image(title, option)) => {
+      return event_queue.push_back(Event::Image(title, option))
+    }
+    Span::Command(CommandSpan::Video(source, option)) => {
+      return event_queue.push_back(Event::Video(source, option))
+    }
+    Span::Inline(text) => return event_queue.push_back(Event::Text(text)),
+    Span::Escaped(character) => return event_queue.push_back(Event::Escaped(character)),
+  };
+  wrap(tag, event_queue, |event_queue| {
+    span_list_events(span_list, event_queue)
+  })
+}
+
+fn wrap<'a, F: FnOnce(&mut VecDeque<Event<'a>>)>(
+  tag: Tag<'a>,
+  event_queue: &mut VecDeque<Event<'a>>,
+  children: F,
+) {
+  event_queue.push_back(Event::Start(tag.clone()));
+  children(event_queue);
+  event_queue.push_back(Event::End(tag));
+}
+
+#[cfg(test)]
+mod tests {
+  use crate::*;
+
+  #[test]
+  fn basic() {
+    let source = "== '''Danuel''' ==\n * [[a|b]]\n----\n##주석";
+    assert_eq!(
+      Parser::new(source).collect::<Vec<_>>(),
+      vec![
+        Event::Start(Tag::OpenHeading(HeadingLevel::Two)),
+        Event::Start(Tag::Strong),
+        Event::Text("Danuel".into()),
+        Event::End(Tag::Strong),
+        Event::End(Tag::OpenHeading(HeadingLevel::Two)),
+        Event::Start(Tag::UnorderedList(1)),
+        Event::Start(Tag::ListItem),
+        Event::Start(Tag::Paragraph),
+        Event::Start(Tag::Link("a".into(), None)),
+        Event::Text("b".into()),
+        Event::End(Tag::Link("a".into(), None)),
+        Event::End(Tag::Paragraph),
+        Event::End(Tag::ListItem),
+        Event::End(Tag::UnorderedList(1)),
+        Event::HorizontalRule,
+        Event::Comment("주석".into()),
+      ]
+    );
+  }
+
+  #[test]
+  fn bracket() {
+    let source = "{{{#!syntax rust fn}}}{{{+1 [br]}}}{{{[[a]]}}}";
+    assert_eq!(
+      Parser::new(source).collect::<Vec<_>>(),
+      vec![
+        Event::Start(Tag::Paragraph),
+        Event::Start(Tag::SyntaxHighlight("rust".into())),
+        Event::Text(" fn".into()),
+        Event::End(Tag::SyntaxHighlight("rust".into())),
+        Event::Start(Tag::SizeUp(FontSizeLevel::One)),
+        Event::Macro(MacroSpan::Linebreak),
+        Event::End(Tag::SizeUp(FontSizeLevel::One)),
+        Event::Code("[[a]]".into()),
+        Event::End(Tag::Paragraph),
+      ]
+    );
+  }
+
+  #[test]
+  fn empty() {
+    assert_eq!(
+      Parser::new("").collect::<Vec<_>>(),
+      vec![Event::Start(Tag::Paragraph), Event::End(Tag::Paragraph)]
+    );
+  }
+
+  #[test]
+  fn with_options() {
+    let options = ParseOptions::new().disable(Syntax::Delete);
+    let event_list: Vec<_> = Parser::new_with("~~a~~", &options).collect();
+    assert_eq!(
+      event_list,
+      vec![
+        Event::Start(Tag::Paragraph),
+        Event::Text("~~a~~".into()),
+        Event::End(Tag::Paragraph),
+      ]
+    );
+  }
+
+  #[test]
+  fn plain_text() {
+    let source = "= 제목 =\n''기울임''[* 각주]\n * {{{#!folding 접기\n내용}}}";
+    let text: String = Parser::new(source)
+      .filter_map(|event| match event {
+        Event::Text(text) | Event::Code(text) => Some(text),
+        _ => None,
+      })
+      .collect();
+    assert_eq!(text, "제목기울임각주접기내용");
+  }
+
+  #[test]
+  fn escaped() {
+    assert_eq!(
+      Parser::new("\\[br]").collect::<Vec<_>>(),
+      vec![
+        Event::Start(Tag::Paragraph),
+        Event::Escaped('['),
+        Event::Text("br]".into()),
+        Event::End(Tag::Paragraph),
+      ]
+    );
+  }
+}
@@ -0,0 +1,434 @@
+use super::Dimensions;
+
+const PNG_SIGNATURE: &[u8] = b"\x89PNG\r\n\x1a\n";
+const GIF87A_SIGNATURE: &[u8] = b"GIF87a";
+const GIF89A_SIGNATURE: &[u8] = b"GIF89a";
+const JPEG_SIGNATURE: &[u8] = b"\xff\xd8";
+const RIFF_SIGNATURE: &[u8] = b"RIFF";
+const WEBP_SIGNATURE: &[u8] = b"WEBP";
+const VP8_START_CODE: &[u8] = b"\x9d\x01\x2a";
+const VP8L_SIGNATURE: u8 = 0x2f;
+/// SVG 문서가 `<svg`보다 먼저 둘 수 있는 것들
+const SVG_PROLOG_LIST: [&[u8]; 4] = [b"<?xml", b"<!--", b"<!DOCTYPE", b"<svg"];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ImageFormat {
+  Png,
+  Jpeg,
+  Gif,
+  WebP,
+  Svg,
+}
+
+/// 이미지 파일의 앞부분에서 읽어낸 정보
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ImageHeader {
+  pub format: ImageFormat,
+  pub dimensions: Dimensions,
+}
+
+impl ImageFormat {
+  pub fn mime_type(&self) -> &'static str {
+    match self {
+      ImageFormat::Png => "image/png",
+      ImageFormat::Jpeg => "image/jpeg",
+      ImageFormat::Gif => "image/gif",
+      ImageFormat::WebP => "image/webp",
+      ImageFormat::Svg => "image/svg+xml",
+    }
+  }
+}
+
+impl ImageHeader {
+  /// 지원하지 않는 형식이거나 헤더가 잘려 있다면 `None`
+  pub fn parse(bytes: &[u8]) -> Option<Self> {
+    if bytes.starts_with(PNG_SIGNATURE) {
+      png(bytes)
+    } else if bytes.starts_with(GIF87A_SIGNATURE) || bytes.starts_with(GIF89A_SIGNATURE) {
+      gif(bytes)
+    } else if bytes.starts_with(JPEG_SIGNATURE) {
+      jpeg(bytes)
+    } else if bytes.starts_with(RIFF_SIGNATURE) && bytes.get(8..12) == Some(WEBP_SIGNATURE) {
+      webp(bytes)
+    } else {
+      svg(bytes)
+    }
+  }
+}
+
+/// 시그니처 바로 뒤의 IHDR 청크에 크기가 있다
+fn png(bytes: &[u8]) -> Option<ImageHeader> {
+  if bytes.get(12..16)? != b"IHDR" {
+    return None;
+  }
+  let width = u32_be(bytes.get(16..20)?);
+  let height = u32_be(bytes.get(20..24)?);
+
+  Some(ImageHeader {
+    format: ImageFormat::Png,
+    dimensions: Dimensions::new(width, height),
+  })
+}
+
+/// 논리 화면 크기를 이미지 크기로 사용한다
+fn gif(bytes: &[u8]) -> Option<ImageHeader> {
+  let width = u16_le(bytes.get(6..8)?);
+  let height = u16_le(bytes.get(8..10)?);
+
+  Some(ImageHeader {
+    format: ImageFormat::Gif,
+    dimensions: Dimensions::new(width.into(), height.into()),
+  })
+}
+
+/// SOF 세그먼트를 만날 때까지 세그먼트를 건너뛴다
+fn jpeg(bytes: &[u8]) -> Option<ImageHeader> {
+  let mut index = JPEG_SIGNATURE.len();
+  loop {
+    while *bytes.get(index)? != 0xff {
+      index += 1;
+    }
+    while *bytes.get(index)? == 0xff {
+      index += 1;
+    }
+    let marker = *bytes.get(index)?;
+    index += 1;
+    match marker {
+      0x01 | 0xd0..=0xd7 => continue,
+      0xd9 | 0xda => return None,
+      _ => {}
+    }
+    let length = usize::from(u16_be(bytes.get(index..index + 2)?));
+    // 길이는 자기 자신의 두 바이트를 포함한다
+    if length < 2 {
+      return None;
+    }
+    if is_start_of_frame(marker) {
+      let height = u16_be(bytes.get(index + 3..index + 5)?);
+      let width = u16_be(bytes.get(index + 5..index + 7)?);
+
+      return Some(ImageHeader {
+        format: ImageFormat::Jpeg,
+        dimensions: Dimensions::new(width.into(), height.into()),
+      });
+    }
+    index += length;
+  }
+}
+
+/// 첫 청크가 `VP8 `(손실), `VP8L`(무손실), `VP8X`(확장) 중 무엇인지에 따라 크기를 읽는 위치가 다르다
+fn webp(bytes: &[u8]) -> Option<ImageHeader> {
+  let data = bytes.get(20..)?;
+  let (width, height) = match bytes.get(12..16)? {
+    b"VP8 " => {
+      if data.get(3..6)? != VP8_START_CODE {
+        return None;
+      }
+      let width = u16_le(data.get(6..8)?) & 0x3fff;
+      let height = u16_le(data.get(8..10)?) & 0x3fff;
+      (u32::from(width), u32::from(height))
+    }
+    b"VP8L" => {
+      if *data.first()? != VP8L_SIGNATURE {
+        return None;
+      }
+      let bits = u32_le(data.get(1..5)?);
+      ((bits & 0x3fff) + 1, ((bits >> 14) & 0x3fff) + 1)
+    }
+    b"VP8X" => (u24_le(data.get(4..7)?) + 1, u24_le(data.get(7..10)?) + 1),
+    _ => return None,
+  };
+
+  Some(ImageHeader {
+    format: ImageFormat::WebP,
+    dimensions: Dimensions::new(width, height),
+  })
+}
+
+/// 최상위 `<svg>` 태그의 `width`, `height`를 쓰고, 없거나 픽셀로 바꿀 수 없는 단위라면 `viewBox`로 채운다
+fn svg(bytes: &[u8]) -> Option<ImageHeader> {
+  let bytes = bytes.strip_prefix(b"\xef\xbb\xbf").unwrap_or(bytes);
+  let start = bytes.iter().position(|byte| !byte.is_ascii_whitespace())?;
+  let bytes = &bytes[start..];
+  if !SVG_PROLOG_LIST
+    .iter()
+    .any(|prolog| bytes.starts_with(prolog))
+  {
+    return None;
+  }
+  let start = bytes.windows(4).position(|window| window == b"<svg")? + 4;
+  let end = start + bytes[start..].iter().position(|&byte| byte == b'>')?;
+  let tag = std::str::from_utf8(&bytes[start..end]).ok()?;
+  if !tag.starts_with(|character: char| character.is_ascii_whitespace() || character == '/') {
+    return None;
+  }
+
+  let mut width = None;
+  let mut height = None;
+  let mut view_box = None;
+  for (name, value) in svg_attributes(tag) {
+    match name {
+      "width" => width = svg_length(value),
+      "height" => height = svg_length(value),
+      "viewBox" => {
+        let value_list: Vec<f64> = value
+          .split(|character: char| character.is_ascii_whitespace() || character == ',')
+          .filter(|value| !value.is_empty())
+          .map(|value| value.parse().unwrap_or(f64::NAN))
+          .collect();
+        if let [_, _, width, height] = value_list[..] {
+          if width > 0.0 && height > 0.0 {
+            view_box = Some((width, height));
+          }
+        }
+      }
+      _ => {}
+    }
+  }
+  let (width, height) = match (width, height, view_box) {
+    (Some(width), Some(height), _) => (width, height),
+    (Some(width), None, Some((box_width, box_height))) => (width, width * box_height / box_width),
+    (None, Some(height), Some((box_width, box_height))) => {
+      (height * box_width / box_height, height)
+    }
+    (None, None, Some(view_box)) => view_box,
+    _ => return None,
+  };
+
+  Some(ImageHeader {
+    format: ImageFormat::Svg,
+    dimensions: Dimensions::new(width.round() as u32, height.round() as u32),
+  })
+}
+
+/// `이름="값"` 또는 `이름='값'` 쌍
+fn svg_attributes(tag: &str) -> impl Iterator<Item = (&str, &str)> {
+  let mut rest = tag;
+  std::iter::from_fn(move || {
+    let name_start = rest.find(|character: char| !character.is_ascii_whitespace())?;
+    rest = &rest[name_start..];
+    let name_end =
+      rest.find(|character: char| character == '=' || character.is_ascii_whitespace())?;
+    let name = &rest[..name_end];
+    rest = rest[name_end..]
+      .trim_start()
+      .strip_prefix('=')?
+      .trim_start();
+    let quote = rest
+      .chars()
+      .next()
+      .filter(|&quote| quote == '"' || quote == '\'')?;
+    rest = &rest[1..];
+    let value_end = rest.find(quote)?;
+    let value = &rest[..value_end];
+    rest = &rest[value_end + 1..];
+
+    Some((name, value))
+  })
+}
+
+/// 단위가 없거나 `px`인 양수만 픽셀로 본다
+fn svg_length(value: &str) -> Option<f64> {
+  let value = value.trim();
+  let number = value.strip_suffix("px").unwrap_or(value);
+  number
+    .parse::<f64>()
+    .ok()
+    .filter(|number| number.is_finite() && *number > 0.0)
+}
+
+fn is_start_of_frame(marker: u8) -> bool {
+  matches!(marker, 0xc0..=0xcf) && !matches!(marker, 0xc4 | 0xc8 | 0xcc)
+}
+
+fn u32_be(bytes: &[u8]) -> u32 {
+  u32::from_be_bytes([bytes[0], bytes[1], bytes[2], bytes[3]])
+}
+
+fn u32_le(bytes: &[u8]) -> u32 {
+  u32::from_le_bytes([bytes[0], bytes[1], bytes[2], bytes[3]])
+}
+
+fn u24_le(bytes: &[u8]) -> u32 {
+  u32::from_le_bytes([bytes[0], bytes[1], bytes[2], 0])
+}
+
+fn u16_be(bytes: &[u8]) -> u16 {
+  u16::from_be_bytes([bytes[0], bytes[1]])
+}
+
+fn u16_le(bytes: &[u8]) -> u16 {
+  u16::from_le_bytes([bytes[0], bytes[1]])
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+  use crate::*;
+
+  pub(crate) fn png_bytes(width: u32, height: u32) -> Vec<u8> {
+    let mut bytes = b"\x89PNG\r\n\x1a\n\x00\x00\x00\x0dIHDR".to_vec();
+    bytes.extend_from_slice(&width.to_be_bytes());
+    bytes.extend_from_slice(&height.to_be_bytes());
+    bytes.extend_from_slice(&[8, 6, 0, 0, 0]);
+    bytes
+  }
+
+  pub(crate) fn gif_bytes(width: u16, height: u16) -> Vec<u8> {
+    let mut bytes = b"GIF89a".to_vec();
+    bytes.extend_from_slice(&width.to_le_bytes());
+    bytes.extend_from_slice(&height.to_le_bytes());
+    bytes
+  }
+
+  pub(crate) fn jpeg_bytes(width: u16, height: u16) -> Vec<u8> {
+    let mut bytes = vec![0xff, 0xd8];
+    // APP0
+    bytes.extend_from_slice(&[0xff, 0xe0, 0x00, 0x06, b'J', b'F', b'I', b'F']);
+    // SOF2
+    bytes.extend_from_slice(&[0xff, 0xc2, 0x00, 0x11, 0x08]);
+    bytes.extend_from_slice(&height.to_be_bytes());
+    bytes.extend_from_slice(&width.to_be_bytes());
+    bytes.extend_from_slice(&[0x03; 10]);
+    bytes
+  }
+
+  fn webp_bytes(chunk: &[u8], data: &[u8]) -> Vec<u8> {
+    let mut bytes = b"RIFF\x00\x00\x00\x00WEBP".to_vec();
+    bytes.extend_from_slice(chunk);
+    bytes.extend_from_slice(&(data.len() as u32).to_le_bytes());
+    bytes.extend_from_slice(data);
+    bytes
+  }
+
+  #[test]
+  fn png() {
+    assert_eq!(
+      ImageHeader::parse(&png_bytes(640, 480)),
+      Some(ImageHeader {
+        format: ImageFormat::Png,
+        dimensions: Dimensions::new(640, 480)
+      })
+    );
+  }
+
+  #[test]
+  fn gif() {
+    assert_eq!(
+      ImageHeader::parse(&gif_bytes(16, 9)),
+      Some(ImageHeader {
+        format: ImageFormat::Gif,
+        dimensions: Dimensions::new(16, 9)
+      })
+    );
+  }
+
+  #[test]
+  fn jpeg() {
+    assert_eq!(
+      ImageHeader::parse(&jpeg_bytes(1920, 1080)),
+      Some(ImageHeader {
+        format: ImageFormat::Jpeg,
+        dimensions: Dimensions::new(1920, 1080)
+      })
+    );
+  }
+
+  #[test]
+  fn webp() {
+    let header = |width, height| {
+      Some(ImageHeader {
+        format: ImageFormat::WebP,
+        dimensions: Dimensions::new(width, height),
+      })
+    };
+    let mut lossy = vec![0x30, 0x01, 0x00, 0x9d, 0x01, 0x2a];
+    lossy.extend_from_slice(&640u16.to_le_bytes());
+    lossy.extend_from_slice(&(480u16 | 0x4000).to_le_bytes());
+    assert_eq!(
+      ImageHeader::parse(&webp_bytes(b"VP8 ", &lossy)),
+      header(640, 480)
+    );
+
+    let bits: u32 = (800 - 1) | ((600 - 1) << 14);
+    let mut lossless = vec![0x2f];
+    lossless.extend_from_slice(&bits.to_le_bytes());
+    assert_eq!(
+      ImageHeader::parse(&webp_bytes(b"VP8L", &lossless)),
+      header(800, 600)
+    );
+
+    let mut extended = vec![0x10, 0x00, 0x00, 0x00];
+    extended.extend_from_slice(&(70000u32 - 1).to_le_bytes()[..3]);
+    extended.extend_from_slice(&(2u32 - 1).to_le_bytes()[..3]);
+    assert_eq!(
+      ImageHeader::parse(&webp_bytes(b"VP8X", &extended)),
+      header(70000, 2)
+    );
+
+    assert_eq!(ImageHeader::parse(&webp_bytes(b"VP8 ", &lossy[..8])), None);
+    assert_eq!(ImageHeader::parse(&webp_bytes(b"ALPH", &lossy)), None);
+  }
+
+  #[test]
+  fn svg() {
+    let header = |width, height| {
+      Some(ImageHeader {
+        format: ImageFormat::Svg,
+        dimensions: Dimensions::new(width, height),
+      })
+    };
+    assert_eq!(
+      ImageHeader::parse(br#"<svg xmlns="http://www.w3.org/2000/svg" width="100" height="50px">"#),
+      header(100, 50)
+    );
+    assert_eq!(
+      ImageHeader::parse(
+        b"\xef\xbb\xbf<?xml version='1.0'?>\n<!-- a -->\n<svg\n  viewBox='0 0 300 150'\n  stroke-width='2'>"
+      ),
+      header(300, 150)
+    );
+    assert_eq!(
+      ImageHeader::parse(br#"<svg width="600" viewBox="0, 0, 300, 150">"#),
+      header(600, 300)
+    );
+    assert_eq!(
+      ImageHeader::parse(br#"<svg width="100%" height="2em" viewBox="0 0 30 15">"#),
+      header(30, 15)
+    );
+    assert_eq!(
+      ImageHeader::parse(br#"<svg width="10" height="20" viewBox="a b c d">"#),
+      header(10, 20)
+    );
+    assert_eq!(ImageHeader::parse(br#"<svg width="100%">"#), None);
+    assert_eq!(
+      ImageHeader::parse(br#"<html><svg width="1" height="1">"#),
+      None
+    );
+    assert_eq!(ImageHeader::parse(br#"<svg width="1" height="1""#), None);
+  }
+
+  #[test]
+  fn jpeg_segment() {
+    let mut zero_length = vec![0xff, 0xd8, 0xff, 0xe0, 0x00, 0x00];
+    zero_length.extend_from_slice(&jpeg_bytes(1, 1)[2..]);
+    assert_eq!(ImageHeader::parse(&zero_length), None);
+
+    let mut one_length = vec![0xff, 0xd8, 0xff, 0xe0, 0x00, 0x01];
+    one_length.extend_from_slice(&jpeg_bytes(1, 1)[2..]);
+    assert_eq!(ImageHeader::parse(&one_length), None);
+
+    let jpeg = jpeg_bytes(1920, 1080);
+    for length in 0..jpeg.len() - 10 {
+      assert_eq!(ImageHeader::parse(&jpeg[..length]), None);
+    }
+    let long_segment = [0xff, 0xd8, 0xff, 0xe0, 0xff, 0xff, 0x00];
+    assert_eq!(ImageHeader::parse(&long_segment), None);
+  }
+
+  #[test]
+  fn truncated() {
+    assert_eq!(ImageHeader::parse(&png_bytes(1, 1)[..20]), None);
+    assert_eq!(ImageHeader::parse(&jpeg_bytes(1, 1)[..12]), None);
+    assert_eq!(ImageHeader::parse(b"plain text"), None);
+  }
+}
@@ -0,0 +1,134 @@
+use super::Dimensions;
+use crate::{ImageOption, Size};
+
+/// `rem` 단위를 픽셀로 바꿀 때 사용하는 글자 크기
+pub const DEFAULT_FONT_SIZE: f32 = 16.0;
+
+/// 렌더러가 `<img width height>`에 넣을 픽셀 크기. 정할 수 없는 값은 `None`
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ImageLayout {
+  pub width: Option<f32>,
+  pub height: Option<f32>,
+}
+
+impl<'a> ImageOption<'a> {
+  /// 지정하지 않은 쪽은 원본 비율에 맞춘다.
+  /// 퍼센트 너비는 `container_width`를, 그것이 없거나 높이라면 원본 크기를 기준으로 한다
+  pub fn layout(
+    &self,
+    dimensions: Option<Dimensions>,
+    container_width: Option<f32>,
+  ) -> ImageLayout {
+    let intrinsic_width = dimensions.map(|dimensions| dimensions.width as f32);
+    let intrinsic_height = dimensions.map(|dimensions| dimensions.height as f32);
+    let width = length(&self.width, container_width.or(intrinsic_width));
+    let height = length(&self.height, intrinsic_height);
+    let aspect_ratio = dimensions.and_then(|dimensions| dimensions.aspect_ratio());
+
+    let (width, height) = match (width, height, aspect_ratio) {
+      (Some(width), None, Some(ratio)) => (Some(width), Some(width / ratio)),
+      (None, Some(height), Some(ratio)) => (Some(height * ratio), Some(height)),
+      (None, None, _) => (intrinsic_width, intrinsic_height),
+      (width, height, _) => (width, height),
+    };
+
+    ImageLayout { width, height }
+  }
+}
+
+fn length(size: &Size, reference: Option<f32>) -> Option<f32> {
+  match size {
+    Size::Auto => None,
+    Size::Numeric(value) | Size::Pixel(value) => Some(*value),
+    Size::Rem(value) => Some(value * DEFAULT_FONT_SIZE),
+    Size::Percent(value) => reference.map(|reference| reference * value / 100.0),
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use crate::*;
+
+  fn layout(width: Size, height: Size, container_width: Option<f32>) -> ImageLayout {
+    let option = ImageOption {
+      width,
+      height,
+      ..Default::default()
+    };
+
+    option.layout(Some(Dimensions::new(800, 600)), container_width)
+  }
+
+  #[test]
+  fn auto() {
+    assert_eq!(
+      layout(Size::Auto, Size::Auto, None),
+      ImageLayout {
+        width: Some(800.0),
+        height: Some(600.0)
+      }
+    );
+  }
+
+  #[test]
+  fn aspect_ratio() {
+    assert_eq!(
+      layout(Size::Pixel(400.0), Size::Auto, None),
+      ImageLayout {
+        width: Some(400.0),
+        height: Some(300.0)
+      }
+    );
+    assert_eq!(
+      layout(Size::Auto, Size::Rem(3.0), None),
+      ImageLayout {
+        width: Some(64.0),
+        height: Some(48.0)
+      }
+    );
+  }
+
+  #[test]
+  fn percent() {
+    assert_eq!(
+      layout(Size::Percent(50.0), Size::Auto, Some(1000.0)),
+      ImageLayout {
+        width: Some(500.0),
+        height: Some(375.0)
+      }
+    );
+    assert_eq!(
+      layout(Size::Percent(50.0), Size::Auto, None),
+      ImageLayout {
+        width: Some(400.0),
+        height: Some(300.0)
+      }
+    );
+  }
+
+  #[test]
+  fn both() {
+    assert_eq!(
+      layout(Size::Numeric(100.0), Size::Numeric(100.0), None),
+      ImageLayout {
+        width: Some(100.0),
+        height: Some(100.0)
+      }
+    );
+  }
+
+  #[test]
+  fn unknown_dimensions() {
+    let option = ImageOption {
+      width: Size::Percent(50.0),
+      ..Default::default()
+    };
+    assert_eq!(
+      option.layout(None, None),
+      ImageLayout {
+        width: None,
+        height: None
+      }
+    );
+  }
+}
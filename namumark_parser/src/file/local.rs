@@ -0,0 +1,239 @@
+use super::{FileInfo, FileProvider, ImageHeader};
+use crate::{percent_encode, PageTitle};
+use std::{
+  env,
+  fs::{self, File},
+  io::Read,
+  path::{Component, Path, PathBuf},
+};
+
+/// 이미지 헤더를 찾기 위해 읽는 최대 바이트 수
+const HEADER_LIMIT: u64 = 1 << 20;
+const LICENSE_EXTENSION: &str = "license";
+const DEFAULT_MIME_TYPE: &str = "application/octet-stream";
+
+/// 디렉터리 안의 파일을 `파일:` 문서로 취급한다.
+/// `a.jpg`의 라이선스는 같은 위치의 `a.jpg.license`에서 읽는다
+#[derive(Debug, Clone, PartialEq)]
+pub struct LocalDirectory {
+  root: PathBuf,
+  base_url: String,
+  namespace: String,
+}
+
+impl LocalDirectory {
+  pub fn new<P: Into<PathBuf>>(root: P) -> Self {
+    let root = root.into();
+    let base_url = file_url(&root);
+
+    LocalDirectory {
+      root,
+      base_url,
+      namespace: "파일".to_owned(),
+    }
+  }
+
+  /// 파일 주소 앞에 붙일 주소. 기본값은 디렉터리의 `file://` 주소
+  pub fn base_url(mut self, base_url: &str) -> Self {
+    self.base_url = base_url.trim_end_matches('/').to_owned();
+    self
+  }
+
+  /// `NamespaceRegistry::file`로 파일 이름공간을 바꿨다면 같은 이름을 지정한다
+  pub fn namespace(mut self, namespace: &str) -> Self {
+    self.namespace = namespace.to_owned();
+    self
+  }
+
+  /// 디렉터리 밖을 가리키는 이름은 거부한다
+  fn path(&self, name: &str) -> Option<PathBuf> {
+    let relative = Path::new(name);
+    let is_inside = relative
+      .components()
+      .all(|component| matches!(component, Component::Normal(_)));
+    if name.is_empty() || !is_inside {
+      return None;
+    }
+
+    Some(self.root.join(relative))
+  }
+}
+
+impl FileProvider for LocalDirectory {
+  fn resolve(&self, title: &PageTitle) -> Option<FileInfo> {
+    if !title.is_in(&self.namespace) {
+      return None;
+    }
+    let path = self.path(&title.name)?;
+    let mut header = vec![];
+    File::open(&path)
+      .ok()?
+      .take(HEADER_LIMIT)
+      .read_to_end(&mut header)
+      .ok()?;
+    let image_header = ImageHeader::parse(&header);
+    let mut license_path = path.into_os_string();
+    license_path.push(".");
+    license_path.push(LICENSE_EXTENSION);
+    let license = fs::read_to_string(license_path)
+      .ok()
+      .map(|license| license.trim().to_owned());
+
+    Some(FileInfo {
+      url: format!("{}/{}", self.base_url, encode_path(&title.name)),
+      mime_type: image_header
+        .map_or(DEFAULT_MIME_TYPE, |header| header.format.mime_type())
+        .to_owned(),
+      dimensions: image_header.map(|header| header.dimensions),
+      license,
+    })
+  }
+}
+
+/// 경로의 각 부분을 인코딩한 `file://` 주소. 상대 경로는 현재 디렉터리를 기준으로 한다
+fn file_url(path: &Path) -> String {
+  let absolute = match env::current_dir() {
+    Ok(current) if path.is_relative() => current.join(path),
+    _ => path.to_path_buf(),
+  };
+  let mut url = "file://".to_owned();
+  for component in absolute.components() {
+    match component {
+      Component::Prefix(prefix) => {
+        url.push('/');
+        url.push_str(&prefix.as_os_str().to_string_lossy().replace('\\', "/"));
+      }
+      Component::RootDir | Component::CurDir => {}
+      Component::ParentDir => url.push_str("/.."),
+      Component::Normal(name) => {
+        url.push('/');
+        url.push_str(&percent_encode(&name.to_string_lossy()));
+      }
+    }
+  }
+
+  url
+}
+
+/// `/`로 나눈 각 부분을 인코딩한다
+fn encode_path(name: &str) -> String {
+  name
+    .split('/')
+    .map(percent_encode)
+    .collect::<Vec<_>>()
+    .join("/")
+}
+
+#[cfg(test)]
+mod tests {
+  use super::super::header::tests::{gif_bytes, jpeg_bytes, png_bytes};
+  use super::file_url;
+  use crate::*;
+  use std::{
+    fs,
+    path::{Path, PathBuf},
+  };
+
+  fn directory(name: &str) -> PathBuf {
+    let path = std::env::temp_dir().join(format!(
+      "namumark-local-directory-{}-{}",
+      name,
+      std::process::id()
+    ));
+    fs::create_dir_all(&path).unwrap();
+    path
+  }
+
+  #[test]
+  fn resolve() {
+    let root = directory("resolve");
+    fs::write(root.join("a.png"), png_bytes(640, 480)).unwrap();
+    fs::write(root.join("a.png.license"), "CC BY-SA 4.0\n").unwrap();
+    fs::write(root.join("b.jpg"), jpeg_bytes(1920, 1080)).unwrap();
+    fs::write(root.join("c.gif"), gif_bytes(16, 9)).unwrap();
+    fs::write(root.join("d.txt"), "plain text").unwrap();
+    let provider = LocalDirectory::new(&root).base_url("https://example.com/files/");
+
+    assert_eq!(
+      provider.resolve(&PageTitle::new(Some("파일"), "a.png")),
+      Some(FileInfo {
+        url: "https://example.com/files/a.png".to_owned(),
+        mime_type: "image/png".to_owned(),
+        dimensions: Some(Dimensions::new(640, 480)),
+        license: Some("CC BY-SA 4.0".to_owned()),
+      })
+    );
+
+    let file = provider
+      .resolve(&PageTitle::new(Some("파일"), "b.jpg"))
+      .unwrap();
+    assert_eq!(file.mime_type, "image/jpeg");
+    assert_eq!(file.dimensions, Some(Dimensions::new(1920, 1080)));
+    assert_eq!(file.license, None);
+
+    let file = provider
+      .resolve(&PageTitle::new(Some("파일"), "c.gif"))
+      .unwrap();
+    assert_eq!(file.dimensions, Some(Dimensions::new(16, 9)));
+
+    let file = provider
+      .resolve(&PageTitle::new(Some("파일"), "d.txt"))
+      .unwrap();
+    assert_eq!(file.mime_type, "application/octet-stream");
+    assert_eq!(file.dimensions, None);
+
+    fs::remove_dir_all(root).unwrap();
+  }
+
+  #[test]
+  fn not_found() {
+    let root = directory("not-found");
+    fs::write(root.join("a.png"), png_bytes(1, 1)).unwrap();
+    let provider = LocalDirectory::new(&root);
+
+    assert_eq!(
+      provider.resolve(&PageTitle::new(Some("파일"), "b.png")),
+      None
+    );
+    assert_eq!(provider.resolve(&PageTitle::new(Some("틀"), "a.png")), None);
+    assert_eq!(provider.resolve(&PageTitle::new(None, "a.png")), None);
+    assert_eq!(
+      provider.resolve(&PageTitle::new(Some("파일"), "../a.png")),
+      None
+    );
+
+    fs::remove_dir_all(root).unwrap();
+  }
+
+  #[test]
+  fn encoded_url() {
+    let root = directory("encoded-url");
+    fs::write(root.join("가 #1.png"), png_bytes(1, 1)).unwrap();
+    let provider = LocalDirectory::new(&root).base_url("https://example.com/files");
+
+    assert_eq!(
+      provider
+        .resolve(&PageTitle::new(Some("파일"), "가 #1.png"))
+        .map(|file| file.url),
+      Some("https://example.com/files/%EA%B0%80%20%231.png".to_owned())
+    );
+
+    let file = LocalDirectory::new(&root)
+      .resolve(&PageTitle::new(Some("파일"), "가 #1.png"))
+      .unwrap();
+    assert!(file.url.starts_with("file:///"));
+    assert!(file.url.starts_with(&file_url(&root)));
+    assert!(file.url.ends_with("/%EA%B0%80%20%231.png"));
+
+    fs::remove_dir_all(root).unwrap();
+  }
+
+  #[cfg(unix)]
+  #[test]
+  fn file_url_encoding() {
+    assert_eq!(
+      file_url(Path::new("/a b/파일/c?d")),
+      "file:///a%20b/%ED%8C%8C%EC%9D%BC/c%3Fd"
+    );
+  }
+}
@@ -0,0 +1,51 @@
+mod header;
+mod layout;
+mod local;
+
+use crate::PageTitle;
+pub use header::{ImageFormat, ImageHeader};
+pub use layout::{ImageLayout, DEFAULT_FONT_SIZE};
+pub use local::LocalDirectory;
+
+/// `파일:` 문서 제목을 실제 파일 정보로 바꾼다
+pub trait FileProvider {
+  /// 찾을 수 없는 파일이면 `None`
+  fn resolve(&self, title: &PageTitle) -> Option<FileInfo>;
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct FileInfo {
+  pub url: String,
+  pub mime_type: String,
+  /// 이미지가 아니거나 헤더를 읽지 못했다면 `None`
+  pub dimensions: Option<Dimensions>,
+  pub license: Option<String>,
+}
+
+/// 픽셀 단위의 원본 크기
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Dimensions {
+  pub width: u32,
+  pub height: u32,
+}
+
+impl Dimensions {
+  pub fn new(width: u32, height: u32) -> Self {
+    Dimensions { width, height }
+  }
+
+  /// 높이에 대한 너비의 비율. 높이가 0이면 `None`
+  pub fn aspect_ratio(&self) -> Option<f32> {
+    if self.height == 0 {
+      None
+    } else {
+      Some(self.width as f32 / self.height as f32)
+    }
+  }
+}
+
+impl<T: FileProvider + ?Sized> FileProvider for &T {
+  fn resolve(&self, title: &PageTitle) -> Option<FileInfo> {
+    (**self).resolve(title)
+  }
+}
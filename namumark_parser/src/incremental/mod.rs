@@ -0,0 +1,363 @@
+use crate::{
+  block::block,
+  rebase::{Rebase, Reborrow},
+  span::bracket_pair_list,
+  Block, Context, ParseOptions,
+};
+use std::{borrow::Cow, ops::Range};
+
+/// `range`를 `replacement`로 바꾸는 편집. 위치는 바이트 단위이다
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TextEdit<'e> {
+  pub start: usize,
+  pub end: usize,
+  pub replacement: &'e str,
+}
+
+/// 블록마다 입력의 어느 위치에서 시작하는지 기억하는 파싱 결과
+#[derive(Debug, PartialEq)]
+pub struct ParseTree<'a> {
+  input: &'a str,
+  block_list: Vec<Block<'a>>,
+  /// 블록이 시작하는 위치. 마지막 원소는 파싱을 마친 위치
+  offset_list: Vec<usize>,
+}
+
+impl<'e> TextEdit<'e> {
+  pub fn new(range: Range<usize>, replacement: &'e str) -> Self {
+    TextEdit {
+      start: range.start,
+      end: range.end,
+      replacement,
+    }
+  }
+
+  pub fn apply(&self, input: &str) -> String {
+    let mut output = String::with_capacity(input.len() + self.replacement.len());
+    output.push_str(&input[..self.start]);
+    output.push_str(self.replacement);
+    output.push_str(&input[self.end..]);
+    output
+  }
+
+  /// 새 입력에서의 위치 - 이전 입력에서의 위치
+  fn delta(&self) -> isize {
+    self.replacement.len() as isize - (self.end - self.start) as isize
+  }
+}
+
+impl<'a> ParseTree<'a> {
+  pub fn parse(input: &'a str) -> Self {
+    Self::parse_with(input, &Default::default())
+  }
+
+  pub fn parse_with(input: &'a str, options: &ParseOptions) -> Self {
+    let mut tree = ParseTree {
+      input,
+      block_list: vec![],
+      offset_list: vec![0],
+    };
+    tree.parse_from(0, Context::new(options), |_| false);
+
+    tree
+  }
+
+  pub fn blocks(&self) -> &[Block<'a>] {
+    &self.block_list
+  }
+
+  pub fn into_blocks(self) -> Vec<Block<'a>> {
+    self.block_list
+  }
+
+  /// 편집의 영향을 받는 최상위 블록만 다시 파싱한다.
+  /// `input`은 이전 입력에 `edit`을 적용한 결과여야 하고, `options`는 이전과 같아야 한다
+  pub fn reparse<'n>(
+    self,
+    input: &'n str,
+    edit: &TextEdit,
+    options: &ParseOptions,
+  ) -> ParseTree<'n> {
+    let old = self.input;
+    debug_assert_eq!(input.len() as isize, old.len() as isize + edit.delta());
+    let anchor = self.anchor(edit.start);
+    let context = Context::new(options);
+    let mut block_list = self.block_list.into_iter();
+    let mut tree = ParseTree {
+      input,
+      block_list: Vec::with_capacity(block_list.len()),
+      offset_list: Vec::with_capacity(self.offset_list.len()),
+    };
+
+    // 편집 앞의 블록은 입력이 같으므로 위치만 옮긴다
+    let rebaser = Rebaser::new(old, input, 0..edit.start, 0);
+    for (block, offset) in block_list.by_ref().take(anchor).zip(&self.offset_list) {
+      tree.push_rebased(block, &rebaser, *offset, context);
+    }
+    tree
+      .offset_list
+      .extend_from_slice(&self.offset_list[..=anchor]);
+
+    // 편집 뒤에서 이전과 같은 블록 경계를 만나면, 그 뒤는 입력이 같으므로 이전 결과를 옮긴다
+    let edit_end = edit.start + edit.replacement.len();
+    let old_offset_list = &self.offset_list;
+    let resynced = tree.parse_from(self.offset_list[anchor], context, |offset| {
+      let old_offset = offset as isize - edit.delta();
+      offset >= edit_end
+        && offset < input.len()
+        && old_offset >= edit.end as isize
+        && old_offset_list
+          .binary_search(&(old_offset as usize))
+          .is_ok()
+    });
+    if let Some(offset) = resynced {
+      let old_offset = (offset as isize - edit.delta()) as usize;
+      let index = old_offset_list.binary_search(&old_offset).unwrap();
+      let rebaser = Rebaser::new(old, input, edit.end..old.len(), edit.delta());
+      let shift = |old_offset: usize| (old_offset as isize + edit.delta()) as usize;
+      for (block, old_offset) in block_list.skip(index - anchor).zip(index..) {
+        tree.push_rebased(block, &rebaser, shift(old_offset_list[old_offset]), context);
+        tree
+          .offset_list
+          .push(shift(old_offset_list[old_offset + 1]));
+      }
+    }
+
+    tree
+  }
+
+  /// 다시 파싱을 시작할 블록. 편집 위치를 포함한 블록의 앞 블록은 다음 줄을 보고 끝났을 수 있으므로 함께 다시 파싱한다.
+  /// 편집 앞에서 닫히지 않은 `{{{`가 있다면 그 블록부터 다시 파싱한다
+  fn anchor(&self, start: usize) -> usize {
+    let block_count = self.block_list.len();
+    // 문서 끝에서 편집하면 편집 위치를 포함한 블록이 없으므로 마지막 블록으로 본다
+    let containing = (0..block_count)
+      .find(|index| self.offset_list[index + 1] > start)
+      .unwrap_or_else(|| block_count.saturating_sub(1));
+    let unclosed = bracket_pair_list(&self.input[..start])
+      .into_iter()
+      .find(|(_, end)| end.is_none())
+      .map(|(offset, _)| offset);
+    let anchor = match unclosed {
+      Some(offset) => (0..block_count)
+        .find(|index| self.offset_list[index + 1] > offset)
+        .unwrap_or(block_count),
+      None => block_count,
+    };
+
+    std::cmp::min(containing.saturating_sub(1), anchor)
+  }
+
+  /// 옮길 수 없는 문자열이 있는 블록은 새 입력에서 다시 파싱한다
+  fn push_rebased<'o>(
+    &mut self,
+    block: Block<'o>,
+    rebaser: &Rebaser<'o, 'a>,
+    offset: usize,
+    context: Context,
+  ) {
+    let block = match block.rebase(rebaser) {
+      Some(block) => block,
+      None => self::block(&self.input[offset..], context).unwrap().1,
+    };
+    self.block_list.push(block);
+  }
+
+  /// `block_list`와 같은 방식으로 파싱하면서 블록 경계를 기록한다.
+  /// `is_resynced`가 참인 경계를 만나면 멈추고 그 위치를 돌려준다
+  fn parse_from<F: Fn(usize) -> bool>(
+    &mut self,
+    offset: usize,
+    context: Context,
+    is_resynced: F,
+  ) -> Option<usize> {
+    let mut input = &self.input[offset..];
+    loop {
+      let offset = self.input.len() - input.len();
+      if is_resynced(offset) {
+        return Some(offset);
+      }
+      match block(input, context) {
+        Ok((next_input, block)) => {
+          self.block_list.push(block);
+          self.offset_list.push(self.input.len() - next_input.len());
+          if next_input.is_empty() {
+            return None;
+          }
+          input = next_input;
+        }
+        Err(_) => return None,
+      }
+    }
+  }
+}
+
+/// 이전 입력을 가리키던 문자열을 새 입력의 같은 내용으로 옮긴다.
+/// `unchanged` 구간 밖을 가리키는 문자열이 있으면 옮기지 않고 `None`을 돌려준다
+struct Rebaser<'o, 'n> {
+  old: &'o str,
+  new: &'n str,
+  unchanged: Range<usize>,
+  /// 새 입력에서의 위치 - 이전 입력에서의 위치
+  delta: isize,
+}
+
+impl<'o, 'n> Rebaser<'o, 'n> {
+  fn new(old: &'o str, new: &'n str, unchanged: Range<usize>, delta: isize) -> Self {
+    Rebaser {
+      old,
+      new,
+      unchanged,
+      delta,
+    }
+  }
+
+  fn str(&self, source: &'o str) -> Option<&'n str> {
+    if source.is_empty() {
+      return Some("");
+    }
+    let start = (source.as_ptr() as usize).checked_sub(self.old.as_ptr() as usize)?;
+    let end = start + source.len();
+    if start < self.unchanged.start || end > self.unchanged.end {
+      return None;
+    }
+    let start = (start as isize + self.delta) as usize;
+
+    self.new.get(start..start + source.len())
+  }
+}
+
+impl<'o, 'n> Reborrow<'o, 'n> for Rebaser<'o, 'n> {
+  fn cow(&self, source: Cow<'o, str>) -> Option<Cow<'n, str>> {
+    match source {
+      Cow::Borrowed(source) => self.str(source).map(Cow::Borrowed),
+      Cow::Owned(source) => Some(Cow::Owned(source)),
+    }
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use crate::*;
+
+  const FRAGMENT_LIST: [&str; 23] = [
+    "Danuel",
+    "나무위키",
+    "\n",
+    "\n\n",
+    "= Danuel =\n",
+    "==# Danuel #==\n",
+    "##Danuel\n",
+    " * ",
+    " 1.#3 ",
+    " a.",
+    "  ",
+    ">",
+    "> ",
+    "----\n",
+    "{{{",
+    "}}}",
+    "{{{#!folding 접기\n",
+    "'''",
+    "~~",
+    "[[Danuel|다뉴엘]]",
+    "[br]",
+    "[ruby(나무, ruby=namu)]",
+    "\\",
+  ];
+
+  /// 외부 의존성 없이 재현 가능한 난수를 만든다
+  struct Random(u64);
+
+  impl Random {
+    fn next(&mut self, bound: usize) -> usize {
+      self.0 = self
+        .0
+        .wrapping_mul(6_364_136_223_846_793_005)
+        .wrapping_add(1_442_695_040_888_963_407);
+      ((self.0 >> 33) % bound as u64) as usize
+    }
+
+    fn text(&mut self, max_len: usize) -> String {
+      (0..self.next(max_len + 1))
+        .map(|_| FRAGMENT_LIST[self.next(FRAGMENT_LIST.len())])
+        .collect()
+    }
+
+    fn boundary(&mut self, input: &str) -> usize {
+      let mut index = self.next(input.len() + 1);
+      while !input.is_char_boundary(index) {
+        index -= 1;
+      }
+      index
+    }
+  }
+
+  #[test]
+  fn basic() {
+    let source = "= Danuel =\nDanuel\n\n * Danuel";
+    let tree = ParseTree::parse(source);
+    assert_eq!(tree.blocks(), &parse(source)[..]);
+
+    let edit = TextEdit::new(11..17, "다뉴엘");
+    let target = edit.apply(source);
+    assert_eq!(target, "= Danuel =\n다뉴엘\n\n * Danuel");
+    let tree = tree.reparse(&target, &edit, &Default::default());
+    assert_eq!(tree.into_blocks(), parse(&target));
+  }
+
+  #[test]
+  fn unclosed_bracket_before_edit() {
+    let source = "{{{#!folding 접기\nDanuel\n\n= Danuel =\n";
+    let tree = ParseTree::parse(source);
+    let edit = TextEdit::new(source.len()..source.len(), "}}}");
+    let target = edit.apply(source);
+    let tree = tree.reparse(&target, &edit, &Default::default());
+    assert_eq!(tree.into_blocks(), parse(&target));
+  }
+
+  #[test]
+  fn append_to_end() {
+    let source = " * a\n ";
+    let tree = ParseTree::parse(source);
+    let edit = TextEdit::new(source.len()..source.len(), "   * b");
+    let target = edit.apply(source);
+    let tree = tree.reparse(&target, &edit, &Default::default());
+    assert_eq!(tree.into_blocks(), parse(&target));
+  }
+
+  #[test]
+  fn randomized() {
+    let options = ParseOptions::default();
+    let mut random = Random(0x5eed);
+    for _ in 0..300 {
+      // 트리가 입력을 빌리므로 편집한 입력을 모두 먼저 만들어 둔다
+      let mut source_list = vec![random.text(24)];
+      let mut edit_list = vec![];
+      for _ in 0..8 {
+        let source = source_list.last().unwrap();
+        // 문서 끝에 이어 쓰는 편집이 가장 흔하므로 자주 섞는다
+        let (start, end) = if random.next(4) == 0 {
+          (source.len(), source.len())
+        } else {
+          let start = random.boundary(source);
+          (start, start + random.boundary(&source[start..]))
+        };
+        let replacement = random.text(3);
+        let target = TextEdit::new(start..end, &replacement).apply(source);
+        edit_list.push((start..end, replacement));
+        source_list.push(target);
+      }
+
+      let mut tree = ParseTree::parse_with(&source_list[0], &options);
+      for (index, (range, replacement)) in edit_list.iter().enumerate() {
+        let (source, target) = (&source_list[index], &source_list[index + 1]);
+        let edit = TextEdit::new(range.clone(), replacement);
+        let reparsed = tree.reparse(target, &edit, &options);
+        let expected = ParseTree::parse_with(target, &options);
+        assert_eq!(reparsed, expected, "{:?} -> {:?}", source, target);
+        tree = reparsed;
+      }
+    }
+  }
+}
@@ -0,0 +1,227 @@
+use memchr::{memchr, memchr2, memchr3, memchr_iter};
+use std::borrow::Cow;
+
+/// 스팬이 시작될 수 있는 바이트. `memchr3`로 한 번에 세 개까지 찾는다
+const MARKER_GROUP_LIST: [&[u8]; 4] = [b"'~-", b"_^,", b"\n{[", b"\\"];
+
+/// 표시가 될 수 있는 바이트만 골라 앞에서부터 내놓는다.
+/// 묶음마다 마지막으로 찾은 위치를 기억하므로, 입력을 묶음 수만큼만 훑는다.
+/// 인라인 스팬을 찾을 때만 쓰고, 블록 문법은 지금처럼 줄 단위로 읽는다
+pub(crate) struct Lexer<'a> {
+  input: &'a [u8],
+  /// 묶음마다 다음 후보의 위치. 더 없으면 `None`
+  candidate_list: [Option<usize>; MARKER_GROUP_LIST.len()],
+}
+
+impl<'a> Lexer<'a> {
+  pub(crate) fn new(input: &'a str) -> Self {
+    let input = input.as_bytes();
+    let mut candidate_list = [None; MARKER_GROUP_LIST.len()];
+    for (candidate, group) in candidate_list.iter_mut().zip(MARKER_GROUP_LIST.iter()) {
+      *candidate = find_any(group, input);
+    }
+
+    Lexer {
+      input,
+      candidate_list,
+    }
+  }
+
+  /// `from` 이후에서 처음 나오는 후보. `from`은 줄어들지 않아야 한다
+  pub(crate) fn next_candidate(&mut self, from: usize) -> Option<usize> {
+    let input = self.input;
+    let mut next = None;
+    for (candidate, group) in self.candidate_list.iter_mut().zip(MARKER_GROUP_LIST.iter()) {
+      if let Some(index) = *candidate {
+        if index < from {
+          *candidate = input
+            .get(from..)
+            .and_then(|rest| find_any(group, rest))
+            .map(|index| from + index);
+        }
+      }
+      next = match (next, *candidate) {
+        (Some(next), Some(index)) => Some(std::cmp::min(next, index)),
+        (next, index) => next.or(index),
+      };
+    }
+
+    next
+  }
+}
+
+/// 앞으로만 움직이는 검색에서 마지막 결과를 기억해, 같은 구간을 다시 찾지 않는다
+pub(crate) struct Finder {
+  needle: &'static str,
+  /// 참이면 `\`로 이스케이프된 위치는 건너뛴다
+  skip_escaped: bool,
+  searched_from: Option<usize>,
+  found: Option<usize>,
+}
+
+impl Finder {
+  pub(crate) fn new(needle: &'static str) -> Self {
+    Finder {
+      needle,
+      skip_escaped: false,
+      searched_from: None,
+      found: None,
+    }
+  }
+
+  pub(crate) fn skip_escaped(mut self, skip_escaped: bool) -> Self {
+    self.skip_escaped = skip_escaped;
+    self
+  }
+
+  /// `from` 이후에서 처음 나오는 위치
+  pub(crate) fn find(&mut self, input: &str, from: usize) -> Option<usize> {
+    let is_cached = match (self.searched_from, self.found) {
+      (Some(searched_from), Some(found)) => searched_from <= from && from <= found,
+      (Some(searched_from), None) => searched_from <= from,
+      (None, _) => false,
+    };
+    if !is_cached {
+      let input = input.as_bytes();
+      let mut start = from;
+      self.searched_from = Some(from);
+      self.found = loop {
+        match find(&input[start..], self.needle.as_bytes()).map(|index| start + index) {
+          // 이스케이프 여부는 앞의 `\` 개수로 정해지므로 `from` 앞까지 본다
+          Some(index) if self.skip_escaped && is_escaped(input, index) => start = index + 1,
+          found => break found,
+        }
+      };
+    }
+
+    self.found
+  }
+}
+
+/// `\`로 이스케이프되지 않은 `needle`의 위치
+pub(crate) fn find_unescaped(haystack: &[u8], needle: &[u8]) -> Option<usize> {
+  let mut from = 0;
+  while let Some(index) = find(&haystack[from..], needle).map(|index| from + index) {
+    if !is_escaped(haystack, index) {
+      return Some(index);
+    }
+    from = index + 1;
+  }
+
+  None
+}
+
+/// `\`를 떼어 내고 뒤의 글자를 그대로 남긴다. 떼어 낼 것이 없으면 빌린 채로 돌려준다
+pub(crate) fn unescape(input: &str) -> Cow<'_, str> {
+  if memchr(b'\\', input.as_bytes()).is_none() {
+    return Cow::Borrowed(input);
+  }
+
+  let mut output = String::with_capacity(input.len());
+  let mut character_list = input.chars();
+  while let Some(character) = character_list.next() {
+    match character {
+      '\\' => output.push(character_list.next().unwrap_or('\\')),
+      character => output.push(character),
+    }
+  }
+
+  Cow::Owned(output)
+}
+
+/// `index`의 바로 앞에 홀수 개의 `\`가 있는지
+pub(crate) fn is_escaped(haystack: &[u8], index: usize) -> bool {
+  let backslash_count = haystack[..index]
+    .iter()
+    .rev()
+    .take_while(|byte| **byte == b'\\')
+    .count();
+
+  backslash_count % 2 == 1
+}
+
+/// `group`의 바이트 가운데 처음 나오는 것의 위치
+fn find_any(group: &[u8], haystack: &[u8]) -> Option<usize> {
+  match *group {
+    [byte] => memchr(byte, haystack),
+    [first, second] => memchr2(first, second, haystack),
+    [first, second, third] => memchr3(first, second, third, haystack),
+    _ => unreachable!("표시 묶음은 한 바이트에서 세 바이트 사이이다"),
+  }
+}
+
+/// 찾는 표시가 세 바이트를 넘지 않으므로, 첫 바이트를 `memchr`로 찾은 뒤 나머지를 비교한다
+pub(crate) fn find(haystack: &[u8], needle: &[u8]) -> Option<usize> {
+  match needle {
+    [] => Some(0),
+    [byte] => memchr(*byte, haystack),
+    [first, ..] => {
+      memchr_iter(*first, haystack).find(|&index| haystack[index..].starts_with(needle))
+    }
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn candidate() {
+    let source = "가나 '''a''' [[b]] c\nd\\e";
+    let mut lexer = Lexer::new(source);
+    let mut candidate_list = vec![];
+    let mut from = 0;
+    while let Some(index) = lexer.next_candidate(from) {
+      candidate_list.push(index);
+      from = index + 1;
+    }
+    let expected: Vec<_> = source
+      .bytes()
+      .enumerate()
+      .filter(|(_, byte)| b"'~-_^,\n{[\\".contains(byte))
+      .map(|(index, _)| index)
+      .collect();
+    assert_eq!(candidate_list, expected);
+  }
+
+  #[test]
+  fn skip_candidate() {
+    let source = "a,b''c";
+    let mut lexer = Lexer::new(source);
+    assert_eq!(lexer.next_candidate(0), Some(1));
+    assert_eq!(lexer.next_candidate(4), Some(4));
+    assert_eq!(lexer.next_candidate(5), None);
+    assert_eq!(lexer.next_candidate(6), None);
+  }
+
+  #[test]
+  fn finder() {
+    let source = "a~b~~c~~";
+    let mut finder = Finder::new("~~");
+    assert_eq!(finder.find(source, 0), Some(3));
+    assert_eq!(finder.find(source, 3), Some(3));
+    assert_eq!(finder.find(source, 4), Some(6));
+    assert_eq!(finder.find(source, 7), None);
+    assert_eq!(find(b"abc", b""), Some(0));
+    assert_eq!(find(b"a]]", b"]"), Some(1));
+  }
+
+  #[test]
+  fn escaped() {
+    assert_eq!(find_unescaped(b"\\{{{a{{{", b"{{{"), Some(5));
+    assert_eq!(find_unescaped(b"\\\\{{{", b"{{{"), Some(2));
+    assert_eq!(find_unescaped(b"\\{{{", b"{{{"), None);
+
+    let source = "a\\]]b\\\\]]";
+    let mut finder = Finder::new("]]").skip_escaped(true);
+    assert_eq!(finder.find(source, 0), Some(7));
+    assert_eq!(finder.find(source, 3), Some(7));
+    assert_eq!(Finder::new("]]").find(source, 0), Some(2));
+  }
+
+  #[test]
+  fn unescape() {
+    assert_eq!(super::unescape("a"), Cow::Borrowed("a"));
+    assert_eq!(super::unescape("a\\|b\\\\c\\"), "a|b\\c\\");
+  }
+}
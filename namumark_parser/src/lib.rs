@@ -1,21 +1,60 @@
 #![warn(clippy::all)]
+mod analysis;
 mod block;
 mod constants;
+mod document;
+#[cfg(feature = "dump")]
+mod dump;
+mod evaluator;
+mod event;
+mod file;
+mod incremental;
+mod lexer;
+mod namespace;
+mod options;
+mod rebase;
 mod span;
 mod utils;
+mod visit;
 
+pub use analysis::{
+  categories, edges, links, Backlink, Edge, EdgeKind, LinkGraph, RedirectChain, RedirectEnd,
+};
 pub(crate) use block::multiline_block_list;
 pub use block::{
-  block_list as parse, Block, HeadingLevel, ListIndex, ListItem, MultilineBlock, SinglelineBlock,
+  parse, parse_with, Block, HeadingLevel, ListIndex, ListItem, MultilineBlock, SinglelineBlock,
 };
 use constants::EMPTY;
-use span::span_list;
+pub use document::{
+  parse_document, parse_document_with, redirect, redirect_with, Document, Redirect,
+};
+#[cfg(feature = "dump")]
+pub use dump::{
+  process_dump, summarize, Diagnostic, DocumentSummary, DumpError, DumpRecord, DumpStats,
+};
+pub use evaluator::{
+  Clock, Date, DateError, DateTime, EvaluateError, Evaluator, FixedClock, FixtureStats,
+  MacroEvaluator, SystemClock, UtcOffset, WikiStats,
+};
+pub use event::{Event, Parser, Tag};
+pub use file::{
+  Dimensions, FileInfo, FileProvider, ImageFormat, ImageHeader, ImageLayout, LocalDirectory,
+  DEFAULT_FONT_SIZE,
+};
+pub use incremental::{ParseTree, TextEdit};
+pub use namespace::{NamespaceRegistry, PageTitle};
+pub(crate) use options::Context;
+pub use options::{MacroHandler, ParseOptions, Syntax};
+pub use span::span_list;
+pub(crate) use span::span_list_with;
 pub use span::{
-  Alignment, BracketSpan, Color, CommandSpan, FontSizeLevel, ImageOption, MacroSpan, RubyOption,
-  SemanticSpan, Size, Span, VideoOption, VideoPlatform,
+  Alignment, BracketSpan, Category, Color, CommandSpan, FontSizeLevel, ImageOption, ImageRendering,
+  MacroArguments, MacroSpan, ObjectFit, RubyOption, SemanticSpan, Size, Span, Theme, VideoOption,
+  VideoPlatform,
 };
 use utils::{
-  line, line_with_bracket, linebreak, pipeline, should_be_empty, whitespace, whitespace1,
+  line, line_with_bracket, linebreak, percent_encode, pipeline, whitespace, whitespace1,
 };
+pub use visit::{Visitor, VisitorMut};
 
 type Result<'a, T = ()> = nom::IResult<&'a str, T>;
@@ -0,0 +1,224 @@
+use std::{borrow::Cow, collections::HashMap, fmt};
+
+const SEPARATOR: char = ':';
+
+/// 위키가 사용하는 이름공간과 별칭 목록
+#[derive(Debug, Clone, PartialEq)]
+pub struct NamespaceRegistry {
+  namespace_list: Vec<String>,
+  alias_map: HashMap<String, String>,
+  file: String,
+  category: String,
+}
+
+/// `이름공간:이름` 형식의 문서 제목. 이름공간은 별칭이 아닌 정식 이름으로 저장한다
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct PageTitle<'a> {
+  pub namespace: Option<Cow<'a, str>>,
+  pub name: Cow<'a, str>,
+}
+
+impl NamespaceRegistry {
+  pub fn new() -> Self {
+    NamespaceRegistry {
+      namespace_list: vec![],
+      alias_map: HashMap::new(),
+      file: "파일".to_owned(),
+      category: "분류".to_owned(),
+    }
+  }
+
+  pub fn register(mut self, namespace: &str) -> Self {
+    if !self.contains(namespace) {
+      self.namespace_list.push(namespace.to_owned());
+    }
+    self
+  }
+
+  /// 별칭을 등록한다. 정식 이름공간이 등록되어 있지 않다면 함께 등록한다
+  pub fn alias(mut self, alias: &str, namespace: &str) -> Self {
+    self = self.register(namespace);
+    self
+      .alias_map
+      .insert(alias.to_owned(), namespace.to_owned());
+    self
+  }
+
+  /// `[[파일:...]]`처럼 이미지로 취급할 이름공간
+  pub fn file(mut self, namespace: &str) -> Self {
+    self.file = namespace.to_owned();
+    self.register(namespace)
+  }
+
+  /// `[[분류:...]]`처럼 분류로 취급할 이름공간
+  pub fn category(mut self, namespace: &str) -> Self {
+    self.category = namespace.to_owned();
+    self.register(namespace)
+  }
+
+  pub fn file_namespace(&self) -> &str {
+    &self.file
+  }
+
+  pub fn category_namespace(&self) -> &str {
+    &self.category
+  }
+
+  /// 별칭을 포함해 등록된 이름공간인지 확인한다
+  pub fn contains(&self, namespace: &str) -> bool {
+    self.resolve(namespace).is_some()
+  }
+
+  /// 별칭을 정식 이름공간으로 바꾼다
+  pub fn resolve(&self, namespace: &str) -> Option<&str> {
+    if let Some(name) = self.namespace_list.iter().find(|name| *name == namespace) {
+      Some(name)
+    } else {
+      self.alias_map.get(namespace).map(String::as_str)
+    }
+  }
+
+  pub fn iter(&self) -> impl Iterator<Item = &str> {
+    self.namespace_list.iter().map(String::as_str)
+  }
+
+  /// 등록되지 않은 접두사는 이름의 일부로 취급한다
+  pub fn title<'a, T: Into<Cow<'a, str>>>(&self, input: T) -> PageTitle<'a> {
+    match input.into() {
+      Cow::Borrowed(input) => self.borrowed_title(input),
+      Cow::Owned(input) => {
+        let PageTitle { namespace, name } = self.borrowed_title(&input);
+        PageTitle {
+          namespace: namespace.map(|namespace| Cow::Owned(namespace.into_owned())),
+          name: Cow::Owned(name.into_owned()),
+        }
+      }
+    }
+  }
+
+  fn borrowed_title<'a>(&self, input: &'a str) -> PageTitle<'a> {
+    if let Some(index) = input.find(SEPARATOR) {
+      let (prefix, name) = (&input[..index], &input[index + SEPARATOR.len_utf8()..]);
+      if let Some(namespace) = self.resolve(prefix) {
+        let namespace = if namespace == prefix {
+          Cow::Borrowed(prefix)
+        } else {
+          Cow::Owned(namespace.to_owned())
+        };
+
+        return PageTitle {
+          namespace: Some(namespace),
+          name: Cow::Borrowed(name),
+        };
+      }
+    }
+
+    PageTitle::new(None, input)
+  }
+}
+
+impl Default for NamespaceRegistry {
+  /// 나무위키의 이름공간
+  fn default() -> Self {
+    [
+      "문서",
+      "틀",
+      "분류",
+      "파일",
+      "사용자",
+      "특수기능",
+      "나무위키",
+      "토론",
+      "휴지통",
+      "투표",
+    ]
+    .iter()
+    .fold(NamespaceRegistry::new(), |registry, namespace| {
+      registry.register(namespace)
+    })
+    .alias("File", "파일")
+    .alias("Category", "분류")
+    .alias("Template", "틀")
+    .alias("User", "사용자")
+  }
+}
+
+impl<'a> PageTitle<'a> {
+  pub fn new(namespace: Option<&'a str>, name: &'a str) -> Self {
+    PageTitle {
+      namespace: namespace.map(Cow::Borrowed),
+      name: Cow::Borrowed(name),
+    }
+  }
+
+  pub fn is_in(&self, namespace: &str) -> bool {
+    self.namespace.as_deref() == Some(namespace)
+  }
+}
+
+impl<'a> fmt::Display for PageTitle<'a> {
+  fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+    match &self.namespace {
+      Some(namespace) => write!(f, "{}{}{}", namespace, SEPARATOR, self.name),
+      None => write!(f, "{}", self.name),
+    }
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use crate::*;
+
+  #[test]
+  fn default() {
+    let registry = NamespaceRegistry::default();
+    assert!(registry.contains("문서"));
+    assert!(registry.contains("틀"));
+    assert!(registry.contains("Template"));
+    assert!(!registry.contains("Help"));
+    assert_eq!(registry.file_namespace(), "파일");
+    assert_eq!(registry.category_namespace(), "분류");
+  }
+
+  #[test]
+  fn register() {
+    let registry = NamespaceRegistry::new().register("Main").register("Main");
+    assert!(registry.contains("Main"));
+    assert_eq!(registry.iter().collect::<Vec<_>>(), vec!["Main"]);
+  }
+
+  #[test]
+  fn alias() {
+    let registry = NamespaceRegistry::new().alias("T", "Template");
+    assert_eq!(registry.resolve("T"), Some("Template"));
+    assert_eq!(registry.resolve("Template"), Some("Template"));
+    assert_eq!(registry.iter().collect::<Vec<_>>(), vec!["Template"]);
+  }
+
+  #[test]
+  fn title() {
+    let registry = NamespaceRegistry::default();
+    assert_eq!(registry.title("틀:foo"), PageTitle::new(Some("틀"), "foo"));
+    assert_eq!(
+      registry.title("File:a.jpg"),
+      PageTitle::new(Some("파일"), "a.jpg")
+    );
+    assert_eq!(
+      registry.title("나무위키:대문"),
+      PageTitle::new(Some("나무위키"), "대문")
+    );
+    assert_eq!(registry.title("foo"), PageTitle::new(None, "foo"));
+    assert_eq!(registry.title("foo:bar"), PageTitle::new(None, "foo:bar"));
+    assert_eq!(
+      registry.title("File:a.jpg".to_owned()),
+      PageTitle::new(Some("파일"), "a.jpg")
+    );
+  }
+
+  #[test]
+  fn display() {
+    let registry = NamespaceRegistry::default();
+    assert_eq!(registry.title("Category:foo").to_string(), "분류:foo");
+    assert_eq!(registry.title("foo").to_string(), "foo");
+  }
+}
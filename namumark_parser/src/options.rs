@@ -0,0 +1,345 @@
+use crate::{MacroArguments, NamespaceRegistry, Result};
+use nom::{error::ErrorKind, Err};
+use std::{
+  collections::{HashMap, HashSet},
+  fmt,
+  sync::Arc,
+};
+
+const DEFAULT_MAX_DEPTH: usize = 64;
+
+/// 파서 설정
+#[derive(Clone)]
+pub struct ParseOptions {
+  namespaces: NamespaceRegistry,
+  macro_map: HashMap<String, Arc<dyn MacroHandler>>,
+  disabled_set: HashSet<Syntax>,
+  max_depth: usize,
+}
+
+/// 끌 수 있는 문법
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Syntax {
+  Redirect,
+  Heading,
+  Comment,
+  Blockquote,
+  HorizontalRule,
+  List,
+  Indent,
+  Strong,
+  Emphasis,
+  Delete,
+  Underline,
+  Superscript,
+  Subscript,
+  Bracket,
+  Folding,
+  Link,
+  Image,
+  Video,
+  Category,
+  Macro,
+  Escape,
+}
+
+/// 사용자 정의 매크로. 받아들인 매크로는 `MacroSpan::Custom`이 되고, 거부하면 일반 텍스트로 남는다
+pub trait MacroHandler: Send + Sync {
+  fn accept(&self, arguments: Option<&MacroArguments>) -> bool;
+}
+
+/// 파서 함수 사이에서 전달되는 상태
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct Context<'o> {
+  pub(crate) options: &'o ParseOptions,
+  depth: usize,
+  /// 바깥에 있는 목록의 수
+  list_depth: usize,
+}
+
+impl ParseOptions {
+  pub fn new() -> Self {
+    Default::default()
+  }
+
+  pub fn namespaces(mut self, namespaces: NamespaceRegistry) -> Self {
+    self.namespaces = namespaces;
+    self
+  }
+
+  /// 인자와 관계없이 `[name]`, `[name(...)]`을 사용자 정의 매크로로 파싱한다
+  pub fn custom_macro(self, name: &str) -> Self {
+    self.macro_handler(name, |_: Option<&MacroArguments>| true)
+  }
+
+  pub fn macro_handler<H: MacroHandler + 'static>(mut self, name: &str, handler: H) -> Self {
+    self.macro_map.insert(name.to_owned(), Arc::new(handler));
+    self
+  }
+
+  pub fn disable(mut self, syntax: Syntax) -> Self {
+    self.disabled_set.insert(syntax);
+    self
+  }
+
+  pub fn enable(mut self, syntax: Syntax) -> Self {
+    self.disabled_set.remove(&syntax);
+    self
+  }
+
+  /// 문단, 리스트, 시맨틱 문법 등이 중첩될 수 있는 최대 깊이. 넘어서는 부분은 일반 텍스트로 남는다
+  pub fn max_depth(mut self, max_depth: usize) -> Self {
+    self.max_depth = max_depth;
+    self
+  }
+
+  pub fn namespace_registry(&self) -> &NamespaceRegistry {
+    &self.namespaces
+  }
+
+  pub fn macro_handler_of(&self, name: &str) -> Option<&dyn MacroHandler> {
+    self.macro_map.get(name).map(|handler| handler.as_ref())
+  }
+
+  pub fn is_enabled(&self, syntax: Syntax) -> bool {
+    // 스팬마다 불리므로, 아무것도 끄지 않았다면 해시를 계산하지 않는다
+    self.disabled_set.is_empty() || !self.disabled_set.contains(&syntax)
+  }
+
+  pub fn depth_limit(&self) -> usize {
+    self.max_depth
+  }
+}
+
+impl Default for ParseOptions {
+  fn default() -> Self {
+    ParseOptions {
+      namespaces: Default::default(),
+      macro_map: HashMap::new(),
+      disabled_set: HashSet::new(),
+      max_depth: DEFAULT_MAX_DEPTH,
+    }
+  }
+}
+
+impl fmt::Debug for ParseOptions {
+  fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+    let mut macro_list: Vec<&String> = self.macro_map.keys().collect();
+    macro_list.sort();
+
+    f.debug_struct("ParseOptions")
+      .field("namespaces", &self.namespaces)
+      .field("macro_list", &macro_list)
+      .field("disabled_set", &self.disabled_set)
+      .field("max_depth", &self.max_depth)
+      .finish()
+  }
+}
+
+impl<F> MacroHandler for F
+where
+  F: Fn(Option<&MacroArguments>) -> bool + Send + Sync,
+{
+  fn accept(&self, arguments: Option<&MacroArguments>) -> bool {
+    self(arguments)
+  }
+}
+
+impl<'o> Context<'o> {
+  pub(crate) fn new(options: &'o ParseOptions) -> Self {
+    Context {
+      options,
+      depth: 0,
+      list_depth: 0,
+    }
+  }
+
+  pub(crate) fn namespaces(&self) -> &'o NamespaceRegistry {
+    &self.options.namespaces
+  }
+
+  pub(crate) fn is_enabled(&self, syntax: Syntax) -> bool {
+    self.options.is_enabled(syntax)
+  }
+
+  /// 문법이 꺼져 있다면 파싱을 실패시킨다
+  pub(crate) fn expect<'a>(&self, input: &'a str, syntax: Syntax) -> Result<'a> {
+    if self.is_enabled(syntax) {
+      Ok((input, ()))
+    } else {
+      Err(Err::Error((input, ErrorKind::Verify)))
+    }
+  }
+
+  pub(crate) fn list_depth(&self) -> usize {
+    self.list_depth
+  }
+
+  /// 목록 항목 안의 상태
+  pub(crate) fn in_list(self) -> Self {
+    Context {
+      list_depth: self.list_depth + 1,
+      ..self
+    }
+  }
+
+  /// 한 단계 안쪽의 상태. 최대 깊이를 넘으면 `None`
+  pub(crate) fn nested(self) -> Option<Self> {
+    if self.depth < self.options.max_depth {
+      Some(Context {
+        depth: self.depth + 1,
+        ..self
+      })
+    } else {
+      None
+    }
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use crate::*;
+
+  #[test]
+  fn custom_macro() {
+    let options = ParseOptions::new()
+      .custom_macro("vote")
+      .custom_macro("youtube-playlist");
+    assert_eq!(
+      parse_with("[vote(a, b)][youtube-playlist(PL0)][vote]", &options),
+      vec![Block::Multiline(MultilineBlock::Paragraph(vec![
+        Span::Macro(MacroSpan::Custom {
+          name: "vote".into(),
+          arguments: Some(MacroArguments::parse("a, b"))
+        }),
+        Span::Macro(MacroSpan::Custom {
+          name: "youtube-playlist".into(),
+          arguments: Some(MacroArguments::parse("PL0"))
+        }),
+        Span::Macro(MacroSpan::Custom {
+          name: "vote".into(),
+          arguments: None
+        })
+      ]))]
+    );
+  }
+
+  #[test]
+  fn unknown_macro() {
+    assert_eq!(
+      parse("[vote(a)]"),
+      vec![Block::Multiline(MultilineBlock::Paragraph(vec![
+        Span::Inline("[vote(a)]".into())
+      ]))]
+    );
+  }
+
+  #[test]
+  fn macro_handler() {
+    let options = ParseOptions::new()
+      .macro_handler("vote", |arguments: Option<&MacroArguments>| {
+        arguments.is_some()
+      });
+    assert_eq!(
+      parse_with("[vote(a)][vote]", &options),
+      vec![Block::Multiline(MultilineBlock::Paragraph(vec![
+        Span::Macro(MacroSpan::Custom {
+          name: "vote".into(),
+          arguments: Some(MacroArguments::parse("a"))
+        }),
+        Span::Inline("[vote]".into())
+      ]))]
+    );
+  }
+
+  #[test]
+  fn custom_macro_overrides_builtin() {
+    let options = ParseOptions::new().custom_macro("date");
+    assert_eq!(
+      parse_with("[date]", &options),
+      vec![Block::Multiline(MultilineBlock::Paragraph(vec![
+        Span::Macro(MacroSpan::Custom {
+          name: "date".into(),
+          arguments: None
+        })
+      ]))]
+    );
+  }
+
+  #[test]
+  fn disable_span() {
+    let options = ParseOptions::new()
+      .disable(Syntax::Underline)
+      .disable(Syntax::Macro);
+    assert_eq!(
+      parse_with("__foo__ [br] ''bar''", &options),
+      vec![Block::Multiline(MultilineBlock::Paragraph(vec![
+        Span::Inline("__foo__ [br] ".into()),
+        Span::Semantic(SemanticSpan::Emphasis(vec![Span::Inline("bar".into())]))
+      ]))]
+    );
+  }
+
+  #[test]
+  fn disable_command() {
+    let options = ParseOptions::new().disable(Syntax::Image);
+    assert_eq!(
+      parse_with("[[파일:a.jpg]]", &options),
+      vec![Block::Multiline(MultilineBlock::Paragraph(vec![
+        Span::Command(CommandSpan::Link(vec![], "파일:a.jpg".into(), None))
+      ]))]
+    );
+
+    let options = ParseOptions::new().disable(Syntax::Link);
+    assert_eq!(
+      parse_with("foo [[bar]]", &options),
+      vec![Block::Multiline(MultilineBlock::Paragraph(vec![
+        Span::Inline("foo [[bar]]".into())
+      ]))]
+    );
+  }
+
+  #[test]
+  fn disable_block() {
+    let options = ParseOptions::new()
+      .disable(Syntax::Heading)
+      .disable(Syntax::Blockquote);
+    assert_eq!(
+      parse_with("foo\n= bar =\n>baz", &options),
+      vec![Block::Multiline(MultilineBlock::Paragraph(vec![
+        Span::Inline("foo".into()),
+        Span::Semantic(SemanticSpan::Linebreak),
+        Span::Inline("= bar =".into()),
+        Span::Semantic(SemanticSpan::Linebreak),
+        Span::Inline(">baz".into())
+      ]))]
+    );
+  }
+
+  #[test]
+  fn max_depth() {
+    let options = ParseOptions::new().max_depth(2);
+    assert_eq!(
+      parse_with("'''a ~~b ,,c,,~~'''", &options),
+      vec![Block::Multiline(MultilineBlock::Paragraph(vec![
+        Span::Semantic(SemanticSpan::Strong(vec![
+          Span::Inline("a ".into()),
+          Span::Semantic(SemanticSpan::Delete(vec![Span::Inline("b ,,c,,".into())]))
+        ]))
+      ]))]
+    );
+  }
+
+  #[test]
+  fn max_depth_of_block() {
+    let options = ParseOptions::new().max_depth(2);
+    assert_eq!(
+      parse_with("   foo", &options),
+      vec![Block::Multiline(MultilineBlock::Indent(vec![
+        MultilineBlock::Indent(vec![MultilineBlock::Indent(vec![
+          MultilineBlock::Paragraph(vec![Span::Inline("foo".into())])
+        ])])
+      ]))]
+    );
+  }
+}
@@ -0,0 +1,402 @@
+use crate::{
+  Block, BracketSpan, Category, Color, CommandSpan, Document, ImageOption, ListItem,
+  MacroArguments, MacroSpan, MultilineBlock, PageTitle, Redirect, RubyOption, SemanticSpan,
+  SinglelineBlock, Span,
+};
+use std::borrow::Cow;
+
+/// 트리 안의 문자열을 수명이 다른 문자열로 바꾼다. 바꿀 수 없으면 `None`을 돌려준다
+pub(crate) trait Reborrow<'o, 'n> {
+  fn cow(&self, source: Cow<'o, str>) -> Option<Cow<'n, str>>;
+}
+
+/// 입력을 빌리던 문자열을 모두 복사한다
+struct IntoOwned;
+
+impl<'o> Reborrow<'o, 'static> for IntoOwned {
+  fn cow(&self, source: Cow<'o, str>) -> Option<Cow<'static, str>> {
+    Some(Cow::Owned(source.into_owned()))
+  }
+}
+
+macro_rules! into_owned {
+  ($($name:ident),*) => {
+    $(
+      impl<'a> $name<'a> {
+        /// 입력을 빌리지 않는 트리로 바꾼다
+        pub fn into_owned(self) -> $name<'static> {
+          self.rebase(&IntoOwned).expect("복사는 실패하지 않는다")
+        }
+      }
+    )*
+  };
+}
+
+into_owned!(
+  Document,
+  Redirect,
+  Block,
+  SinglelineBlock,
+  MultilineBlock,
+  ListItem,
+  Span,
+  SemanticSpan,
+  BracketSpan,
+  Color,
+  MacroSpan,
+  MacroArguments,
+  RubyOption,
+  PageTitle,
+  CommandSpan,
+  Category,
+  ImageOption
+);
+
+pub(crate) trait Rebase<'o, 'n>: Sized {
+  type Output;
+
+  fn rebase<R: Reborrow<'o, 'n>>(self, rebaser: &R) -> Option<Self::Output>;
+}
+
+impl<'o, 'n, T: Rebase<'o, 'n>> Rebase<'o, 'n> for Vec<T> {
+  type Output = Vec<T::Output>;
+
+  fn rebase<R: Reborrow<'o, 'n>>(self, rebaser: &R) -> Option<Self::Output> {
+    self.into_iter().map(|item| item.rebase(rebaser)).collect()
+  }
+}
+
+impl<'o, 'n> Rebase<'o, 'n> for Document<'o> {
+  type Output = Document<'n>;
+
+  fn rebase<R: Reborrow<'o, 'n>>(self, rebaser: &R) -> Option<Self::Output> {
+    Some(match self {
+      Document::Redirect(redirect) => Document::Redirect(redirect.rebase(rebaser)?),
+      Document::Content(block_list) => Document::Content(block_list.rebase(rebaser)?),
+    })
+  }
+}
+
+impl<'o, 'n> Rebase<'o, 'n> for Redirect<'o> {
+  type Output = Redirect<'n>;
+
+  fn rebase<R: Reborrow<'o, 'n>>(self, rebaser: &R) -> Option<Self::Output> {
+    Some(Redirect {
+      title: self.title.rebase(rebaser)?,
+      anchor: match self.anchor {
+        Some(anchor) => Some(rebaser.cow(anchor)?),
+        None => None,
+      },
+    })
+  }
+}
+
+impl<'o, 'n> Rebase<'o, 'n> for Block<'o> {
+  type Output = Block<'n>;
+
+  fn rebase<R: Reborrow<'o, 'n>>(self, rebaser: &R) -> Option<Self::Output> {
+    Some(match self {
+      Block::Singleline(block) => Block::Singleline(block.rebase(rebaser)?),
+      Block::Multiline(block) => Block::Multiline(block.rebase(rebaser)?),
+    })
+  }
+}
+
+impl<'o, 'n> Rebase<'o, 'n> for SinglelineBlock<'o> {
+  type Output = SinglelineBlock<'n>;
+
+  fn rebase<R: Reborrow<'o, 'n>>(self, rebaser: &R) -> Option<Self::Output> {
+    Some(match self {
+      SinglelineBlock::OpenHeading(span_list, level) => {
+        SinglelineBlock::OpenHeading(span_list.rebase(rebaser)?, level)
+      }
+      SinglelineBlock::ClosedHeading(span_list, level) => {
+        SinglelineBlock::ClosedHeading(span_list.rebase(rebaser)?, level)
+      }
+      SinglelineBlock::Comment(comment) => SinglelineBlock::Comment(rebaser.cow(comment)?),
+    })
+  }
+}
+
+impl<'o, 'n> Rebase<'o, 'n> for MultilineBlock<'o> {
+  type Output = MultilineBlock<'n>;
+
+  fn rebase<R: Reborrow<'o, 'n>>(self, rebaser: &R) -> Option<Self::Output> {
+    Some(match self {
+      MultilineBlock::Blockquote(block_list) => {
+        MultilineBlock::Blockquote(block_list.rebase(rebaser)?)
+      }
+      MultilineBlock::HorizontalRule => MultilineBlock::HorizontalRule,
+      MultilineBlock::Indent(block_list) => MultilineBlock::Indent(block_list.rebase(rebaser)?),
+      MultilineBlock::OrderedList(item_list, index, depth) => {
+        MultilineBlock::OrderedList(item_list.rebase(rebaser)?, index, depth)
+      }
+      MultilineBlock::Paragraph(span_list) => MultilineBlock::Paragraph(span_list.rebase(rebaser)?),
+      MultilineBlock::UnorderedList(item_list, depth) => {
+        MultilineBlock::UnorderedList(item_list.rebase(rebaser)?, depth)
+      }
+    })
+  }
+}
+
+impl<'o, 'n> Rebase<'o, 'n> for ListItem<'o> {
+  type Output = ListItem<'n>;
+
+  fn rebase<R: Reborrow<'o, 'n>>(self, rebaser: &R) -> Option<Self::Output> {
+    Some(ListItem(self.0.rebase(rebaser)?))
+  }
+}
+
+impl<'o, 'n> Rebase<'o, 'n> for Span<'o> {
+  type Output = Span<'n>;
+
+  fn rebase<R: Reborrow<'o, 'n>>(self, rebaser: &R) -> Option<Self::Output> {
+    Some(match self {
+      Span::Semantic(span) => Span::Semantic(span.rebase(rebaser)?),
+      Span::Bracket(span) => Span::Bracket(span.rebase(rebaser)?),
+      Span::Macro(span) => Span::Macro(span.rebase(rebaser)?),
+      Span::Command(span) => Span::Command(span.rebase(rebaser)?),
+      Span::Inline(inline) => Span::Inline(rebaser.cow(inline)?),
+      Span::Escaped(character) => Span::Escaped(character),
+    })
+  }
+}
+
+impl<'o, 'n> Rebase<'o, 'n> for SemanticSpan<'o> {
+  type Output = SemanticSpan<'n>;
+
+  fn rebase<R: Reborrow<'o, 'n>>(self, rebaser: &R) -> Option<Self::Output> {
+    Some(match self {
+      SemanticSpan::Delete(span_list) => SemanticSpan::Delete(span_list.rebase(rebaser)?),
+      SemanticSpan::Emphasis(span_list) => SemanticSpan::Emphasis(span_list.rebase(rebaser)?),
+      SemanticSpan::Strong(span_list) => SemanticSpan::Strong(span_list.rebase(rebaser)?),
+      SemanticSpan::Subscript(span_list) => SemanticSpan::Subscript(span_list.rebase(rebaser)?),
+      SemanticSpan::Superscript(span_list) => SemanticSpan::Superscript(span_list.rebase(rebaser)?),
+      SemanticSpan::Underline(span_list) => SemanticSpan::Underline(span_list.rebase(rebaser)?),
+      SemanticSpan::Linebreak => SemanticSpan::Linebreak,
+    })
+  }
+}
+
+impl<'o, 'n> Rebase<'o, 'n> for BracketSpan<'o> {
+  type Output = BracketSpan<'n>;
+
+  fn rebase<R: Reborrow<'o, 'n>>(self, rebaser: &R) -> Option<Self::Output> {
+    Some(match self {
+      BracketSpan::Color(span_list, color) => {
+        BracketSpan::Color(span_list.rebase(rebaser)?, color.rebase(rebaser)?)
+      }
+      BracketSpan::Folding(block_list) => BracketSpan::Folding(block_list.rebase(rebaser)?),
+      BracketSpan::Inline(inline) => BracketSpan::Inline(rebaser.cow(inline)?),
+      BracketSpan::SizeDown(span_list, level) => {
+        BracketSpan::SizeDown(span_list.rebase(rebaser)?, level)
+      }
+      BracketSpan::SizeUp(span_list, level) => {
+        BracketSpan::SizeUp(span_list.rebase(rebaser)?, level)
+      }
+      BracketSpan::SyntaxHighlight(code, language) => {
+        BracketSpan::SyntaxHighlight(rebaser.cow(code)?, rebaser.cow(language)?)
+      }
+    })
+  }
+}
+
+impl<'o, 'n> Rebase<'o, 'n> for Color<'o> {
+  type Output = Color<'n>;
+
+  fn rebase<R: Reborrow<'o, 'n>>(self, rebaser: &R) -> Option<Self::Output> {
+    Some(match self {
+      Color::Hex(r, g, b) => Color::Hex(r, g, b),
+      Color::Rgb(r, g, b) => Color::Rgb(r, g, b),
+      Color::Hsl(h, s, l) => Color::Hsl(h, s, l),
+      Color::Raw(raw) => Color::Raw(rebaser.cow(raw)?),
+    })
+  }
+}
+
+impl<'o, 'n> Rebase<'o, 'n> for MacroSpan<'o> {
+  type Output = MacroSpan<'n>;
+
+  fn rebase<R: Reborrow<'o, 'n>>(self, rebaser: &R) -> Option<Self::Output> {
+    Some(match self {
+      MacroSpan::Age(date) => MacroSpan::Age(rebaser.cow(date)?),
+      MacroSpan::Anchor(name) => MacroSpan::Anchor(rebaser.cow(name)?),
+      MacroSpan::Comment(span_list, description) => {
+        MacroSpan::Comment(span_list.rebase(rebaser)?, rebaser.cow(description)?)
+      }
+      MacroSpan::Custom { name, arguments } => MacroSpan::Custom {
+        name: rebaser.cow(name)?,
+        arguments: match arguments {
+          Some(arguments) => Some(arguments.rebase(rebaser)?),
+          None => None,
+        },
+      },
+      MacroSpan::Date => MacroSpan::Date,
+      MacroSpan::Datetime => MacroSpan::Datetime,
+      MacroSpan::Dday(date) => MacroSpan::Dday(rebaser.cow(date)?),
+      MacroSpan::Footnote => MacroSpan::Footnote,
+      MacroSpan::Include(title, parameter_list) => MacroSpan::Include(
+        title.rebase(rebaser)?,
+        parameter_list
+          .into_iter()
+          .map(|(key, value)| Some((rebaser.cow(key)?, rebaser.cow(value)?)))
+          .collect::<Option<_>>()?,
+      ),
+      MacroSpan::Latex(latex) => MacroSpan::Latex(rebaser.cow(latex)?),
+      MacroSpan::Linebreak => MacroSpan::Linebreak,
+      MacroSpan::PageCount(namespace) => MacroSpan::PageCount(match namespace {
+        Some(namespace) => Some(rebaser.cow(namespace)?),
+        None => None,
+      }),
+      MacroSpan::Ruby(ruby) => MacroSpan::Ruby(match ruby {
+        Some((word, option)) => Some((rebaser.cow(word)?, option.rebase(rebaser)?)),
+        None => None,
+      }),
+      MacroSpan::TableOfContents => MacroSpan::TableOfContents,
+    })
+  }
+}
+
+impl<'o, 'n> Rebase<'o, 'n> for MacroArguments<'o> {
+  type Output = MacroArguments<'n>;
+
+  fn rebase<R: Reborrow<'o, 'n>>(self, rebaser: &R) -> Option<Self::Output> {
+    let (positional_list, named_list) = self.into_parts();
+    let positional_list = positional_list
+      .into_iter()
+      .map(|value| rebaser.cow(value))
+      .collect::<Option<_>>()?;
+    let named_list = named_list
+      .into_iter()
+      .map(|(key, value)| Some((rebaser.cow(key)?, rebaser.cow(value)?)))
+      .collect::<Option<_>>()?;
+
+    Some(MacroArguments::from_parts(positional_list, named_list))
+  }
+}
+
+impl<'o, 'n> Rebase<'o, 'n> for RubyOption<'o> {
+  type Output = RubyOption<'n>;
+
+  fn rebase<R: Reborrow<'o, 'n>>(self, rebaser: &R) -> Option<Self::Output> {
+    Some(RubyOption {
+      color: self.color.rebase(rebaser)?,
+      text: rebaser.cow(self.text)?,
+    })
+  }
+}
+
+impl<'o, 'n> Rebase<'o, 'n> for PageTitle<'o> {
+  type Output = PageTitle<'n>;
+
+  fn rebase<R: Reborrow<'o, 'n>>(self, rebaser: &R) -> Option<Self::Output> {
+    Some(PageTitle {
+      namespace: match self.namespace {
+        Some(namespace) => Some(rebaser.cow(namespace)?),
+        None => None,
+      },
+      name: rebaser.cow(self.name)?,
+    })
+  }
+}
+
+impl<'o, 'n> Rebase<'o, 'n> for CommandSpan<'o> {
+  type Output = CommandSpan<'n>;
+
+  fn rebase<R: Reborrow<'o, 'n>>(self, rebaser: &R) -> Option<Self::Output> {
+    Some(match self {
+      CommandSpan::Category(category) => CommandSpan::Category(category.rebase(rebaser)?),
+      CommandSpan::Image(title, option) => {
+        CommandSpan::Image(title.rebase(rebaser)?, option.rebase(rebaser)?)
+      }
+      CommandSpan::Link(span_list, link, anchor) => CommandSpan::Link(
+        span_list.rebase(rebaser)?,
+        rebaser.cow(link)?,
+        match anchor {
+          Some(anchor) => Some(rebaser.cow(anchor)?),
+          None => None,
+        },
+      ),
+      CommandSpan::Video(source, option) => CommandSpan::Video(rebaser.cow(source)?, option),
+    })
+  }
+}
+
+impl<'o, 'n> Rebase<'o, 'n> for Category<'o> {
+  type Output = Category<'n>;
+
+  fn rebase<R: Reborrow<'o, 'n>>(self, rebaser: &R) -> Option<Self::Output> {
+    Some(Category {
+      title: self.title.rebase(rebaser)?,
+      sort_key: match self.sort_key {
+        Some(sort_key) => Some(rebaser.cow(sort_key)?),
+        None => None,
+      },
+      blur: self.blur,
+    })
+  }
+}
+
+impl<'o, 'n> Rebase<'o, 'n> for ImageOption<'o> {
+  type Output = ImageOption<'n>;
+
+  fn rebase<R: Reborrow<'o, 'n>>(self, rebaser: &R) -> Option<Self::Output> {
+    Some(ImageOption {
+      width: self.width,
+      height: self.height,
+      align: self.align,
+      background_color: match self.background_color {
+        Some(color) => Some(color.rebase(rebaser)?),
+        None => None,
+      },
+      theme: self.theme,
+      border_radius: self.border_radius,
+      object_fit: self.object_fit,
+      rendering: self.rendering,
+    })
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use crate::*;
+  use std::borrow::Cow;
+
+  #[test]
+  fn into_owned() {
+    let source = String::from("= [[Danuel|다뉴엘]] =\n##Danuel\n * '''Danuel'''");
+    let block_list: Vec<Block<'static>> =
+      parse(&source).into_iter().map(Block::into_owned).collect();
+    drop(source);
+    assert_eq!(
+      block_list,
+      parse("= [[Danuel|다뉴엘]] =\n##Danuel\n * '''Danuel'''")
+    );
+    match &block_list[1] {
+      Block::Singleline(SinglelineBlock::Comment(Cow::Owned(comment))) => {
+        assert_eq!(comment, "Danuel")
+      }
+      block => panic!("{:?}", block),
+    }
+  }
+
+  #[test]
+  fn redirect_into_owned() {
+    let document = {
+      let source = String::from("#redirect 나무위키#s-1");
+      parse_document(&source).into_owned()
+    };
+    assert_eq!(document, parse_document("#redirect 나무위키#s-1"));
+  }
+
+  #[test]
+  fn across_threads() {
+    let source = String::from("[[Danuel]] [br]");
+    let span_list: Vec<Span<'static>> = span_list(&source)
+      .into_iter()
+      .map(Span::into_owned)
+      .collect();
+    let handle = std::thread::spawn(move || span_list.len());
+    assert_eq!(handle.join().unwrap(), 3);
+  }
+}
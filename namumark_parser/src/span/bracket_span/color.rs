@@ -1,10 +1,10 @@
-use crate::{span_list, whitespace1, BracketSpan, Color, Result, EMPTY};
+use crate::{span_list_with, whitespace1, BracketSpan, Color, Context, Result, EMPTY};
 use nom::bytes::complete::{is_a, tag, take};
 
-pub(crate) fn color(input: &str) -> Result<BracketSpan> {
+pub(crate) fn color<'a>(input: &'a str, context: Context) -> Result<'a, BracketSpan<'a>> {
   let (input, (code, span_input)) = expect_color(input)?;
-  let span_list = span_list(span_input);
-  let span = BracketSpan::Color(span_list, Color::Raw(code));
+  let span_list = span_list_with(span_input, context);
+  let span = BracketSpan::Color(span_list, Color::Raw(code.into()));
 
   Ok((input, span))
 }
@@ -37,8 +37,8 @@ mod tests {
     assert_eq!(
       span_list(source),
       vec![Span::Bracket(BracketSpan::Color(
-        vec![Span::Inline("Danuel")],
-        Color::Raw("000000")
+        vec![Span::Inline("Danuel".into())],
+        Color::Raw("000000".into())
       ))]
     );
   }
@@ -1,9 +1,10 @@
-use crate::{multiline_block_list, BracketSpan, Result, EMPTY};
+use crate::{multiline_block_list, BracketSpan, Context, Result, Syntax, EMPTY};
 use nom::bytes::complete::tag;
 
-pub(crate) fn folding(input: &str) -> Result<BracketSpan> {
+pub(crate) fn folding<'a>(input: &'a str, context: Context) -> Result<'a, BracketSpan<'a>> {
+  let (input, _) = context.expect(input, Syntax::Folding)?;
   let (input, _) = tag("#!folding ")(input)?;
-  let block_list = multiline_block_list(input);
+  let block_list = multiline_block_list(input, context);
   let span = BracketSpan::Folding(block_list);
 
   Ok((EMPTY, span))
@@ -19,7 +20,7 @@ mod tests {
     assert_eq!(
       span_list(source),
       vec![Span::Bracket(BracketSpan::Folding(vec![
-        MultilineBlock::Paragraph(vec![Span::Inline("Danuel")])
+        MultilineBlock::Paragraph(vec![Span::Inline("Danuel".into())])
       ]))]
     )
   }
@@ -5,34 +5,43 @@ mod size_down;
 mod size_up;
 mod syntax_highlight;
 
-use crate::{Color, FontSizeLevel, MultilineBlock, Result, Span};
+use crate::{Color, Context, FontSizeLevel, MultilineBlock, Result, Span, Syntax};
 use color::color;
 use folding::folding;
 use inline::inline;
 use nom::{branch::alt, bytes::complete::tag, error::ErrorKind, Err};
 use size_down::size_down;
 use size_up::size_up;
+use std::borrow::Cow;
 use syntax_highlight::syntax_highlight;
 
 const SIZE_LEVEL: &str = "12345";
+const START: &str = "{{{";
+const END: &str = "}}}";
 
 #[derive(Debug, PartialEq)]
 pub enum BracketSpan<'a> {
   Color(Vec<Span<'a>>, Color<'a>),
   Folding(Vec<MultilineBlock<'a>>),
-  Inline(&'a str),
+  Inline(Cow<'a, str>),
   SizeDown(Vec<Span<'a>>, FontSizeLevel),
   SizeUp(Vec<Span<'a>>, FontSizeLevel),
-  SyntaxHighlight(&'a str, &'a str),
+  SyntaxHighlight(Cow<'a, str>, Cow<'a, str>),
 }
 
-pub(crate) fn bracket_span(input: &str) -> Result<BracketSpan> {
+pub(crate) fn bracket_span<'a>(input: &'a str, context: Context) -> Result<'a, BracketSpan<'a>> {
+  let (input, _) = context.expect(input, Syntax::Bracket)?;
   let _ = tag("{{{")(input)?;
   let (input, bracket) = line_with_bracket(input);
   if let Some(bracket) = bracket {
-    let (_, span) = alt((size_up, size_down, color, folding, syntax_highlight, inline))(
-      &bracket[3..bracket.len() - 3],
-    )?;
+    let (_, span) = alt((
+      |input| size_up(input, context),
+      |input| size_down(input, context),
+      |input| color(input, context),
+      |input| folding(input, context),
+      syntax_highlight,
+      inline,
+    ))(&bracket[3..bracket.len() - 3])?;
 
     Ok((input, span))
   } else {
@@ -40,16 +49,40 @@ pub(crate) fn bracket_span(input: &str) -> Result<BracketSpan> {
   }
 }
 
-pub(crate) fn starts_with_bracket_span(input: &str) -> bool {
-  let (_, line) = line_with_bracket(input);
+/// 입력 전체에서 `{{{`의 위치와 짝이 되는 `}}}`의 위치를 앞에서부터 나열한다.
+/// `line_with_bracket`과 같은 방식으로 한 번만 훑으므로, 위치마다 다시 찾지 않아도 된다
+pub(crate) fn bracket_pair_list(input: &str) -> Vec<(usize, Option<usize>)> {
+  let mut pair_list: Vec<(usize, Option<usize>)> = vec![];
+  let mut index = 0;
+  let mut index_stack = vec![];
 
-  line.is_some()
+  while index < input.len() {
+    match input.get(index..index + START.len()) {
+      Some(START) => {
+        index_stack.push(pair_list.len());
+        pair_list.push((index, None));
+        index += START.len();
+      }
+      Some(END) => {
+        if let Some(pair_index) = index_stack.pop() {
+          pair_list[pair_index].1 = Some(index);
+        }
+        index += END.len();
+      }
+      // 괄호 밖의 `\`는 다음 글자를 이스케이프한다
+      _ if index_stack.is_empty() && input.as_bytes()[index] == b'\\' => {
+        index += 2;
+      }
+      _ => {
+        index += 1;
+      }
+    }
+  }
+
+  pair_list
 }
 
 fn line_with_bracket(input: &str) -> (&str, Option<&str>) {
-  const START: &str = "{{{";
-  const END: &str = "}}}";
-
   let mut range: Option<(usize, usize)> = None;
   let mut index = 0;
   let mut index_stack = vec![];
@@ -73,6 +106,10 @@ fn line_with_bracket(input: &str) -> (&str, Option<&str>) {
           break;
         }
       }
+      // 괄호 밖의 `\`는 다음 글자를 이스케이프한다
+      _ if index_stack.is_empty() && input.as_bytes()[index] == b'\\' => {
+        index += 2;
+      }
       _ => {
         index += 1;
       }
@@ -1,10 +1,10 @@
 use super::SIZE_LEVEL;
-use crate::{span_list, whitespace1, BracketSpan, Result};
+use crate::{span_list_with, whitespace1, BracketSpan, Context, Result};
 use nom::bytes::complete::{is_a, tag, take};
 
-pub(crate) fn size_down(input: &str) -> Result<BracketSpan> {
+pub(crate) fn size_down<'a>(input: &'a str, context: Context) -> Result<'a, BracketSpan<'a>> {
   let (input, (level, span_input)) = expect_size_down(input)?;
-  let span_list = span_list(span_input);
+  let span_list = span_list_with(span_input, context);
   let span = BracketSpan::SizeDown(span_list, level.into());
 
   Ok((input, span))
@@ -36,7 +36,7 @@ mod tests {
     assert_eq!(
       span_list(source),
       vec![Span::Bracket(BracketSpan::SizeDown(
-        vec![Span::Inline("Danuel")],
+        vec![Span::Inline("Danuel".into())],
         FontSizeLevel::One
       ))]
     );
@@ -48,7 +48,7 @@ mod tests {
     assert_eq!(
       span_list(source),
       vec![Span::Bracket(BracketSpan::SizeDown(
-        vec![Span::Inline("Danuel")],
+        vec![Span::Inline("Danuel".into())],
         FontSizeLevel::Two
       ))]
     );
@@ -60,7 +60,7 @@ mod tests {
     assert_eq!(
       span_list(source),
       vec![Span::Bracket(BracketSpan::SizeDown(
-        vec![Span::Inline("Danuel")],
+        vec![Span::Inline("Danuel".into())],
         FontSizeLevel::Three
       ))]
     );
@@ -72,7 +72,7 @@ mod tests {
     assert_eq!(
       span_list(source),
       vec![Span::Bracket(BracketSpan::SizeDown(
-        vec![Span::Inline("Danuel")],
+        vec![Span::Inline("Danuel".into())],
         FontSizeLevel::Four
       ))]
     );
@@ -84,7 +84,7 @@ mod tests {
     assert_eq!(
       span_list(source),
       vec![Span::Bracket(BracketSpan::SizeDown(
-        vec![Span::Inline("Danuel")],
+        vec![Span::Inline("Danuel".into())],
         FontSizeLevel::Five
       ))]
     );
@@ -4,7 +4,7 @@ use nom::{branch::alt, bytes::complete::tag};
 pub(crate) fn syntax_highlight(input: &str) -> Result<BracketSpan> {
   let (input, _) = identifier(input)?;
   let (input, language) = language(input)?;
-  let span = BracketSpan::SyntaxHighlight(input, language);
+  let span = BracketSpan::SyntaxHighlight(input.into(), language.into());
 
   Ok((EMPTY, span))
 }
@@ -29,8 +29,8 @@ mod tests {
     assert_eq!(
       span_list(source),
       vec![Span::Bracket(BracketSpan::SyntaxHighlight(
-        "",
-        "javascript"
+        "".into(),
+        "javascript".into()
       ))]
     )
   }
@@ -41,8 +41,8 @@ mod tests {
     assert_eq!(
       span_list(source),
       vec![Span::Bracket(BracketSpan::SyntaxHighlight(
-        " code",
-        "javascript"
+        " code".into(),
+        "javascript".into()
       ))]
     )
   }
@@ -55,8 +55,8 @@ code
     assert_eq!(
       span_list(source),
       vec![Span::Bracket(BracketSpan::SyntaxHighlight(
-        "\ncode\n",
-        "javascript"
+        "\ncode\n".into(),
+        "javascript".into()
       ))]
     )
   }
@@ -66,7 +66,10 @@ code
     let source = "{{{#!syntax rust}}}";
     assert_eq!(
       span_list(source),
-      vec![Span::Bracket(BracketSpan::SyntaxHighlight("", "rust"))]
+      vec![Span::Bracket(BracketSpan::SyntaxHighlight(
+        "".into(),
+        "rust".into()
+      ))]
     )
   }
 
@@ -75,7 +78,10 @@ code
     let source = "{{{#!syntax rust code}}}";
     assert_eq!(
       span_list(source),
-      vec![Span::Bracket(BracketSpan::SyntaxHighlight(" code", "rust"))]
+      vec![Span::Bracket(BracketSpan::SyntaxHighlight(
+        " code".into(),
+        "rust".into()
+      ))]
     )
   }
 
@@ -87,7 +93,8 @@ code
     assert_eq!(
       span_list(source),
       vec![Span::Bracket(BracketSpan::SyntaxHighlight(
-        "\ncode\n", "rust"
+        "\ncode\n".into(),
+        "rust".into()
       ))]
     )
   }
@@ -1,17 +1,31 @@
-use crate::{CommandSpan, Result, EMPTY};
-use nom::bytes::complete::tag;
+use crate::{pipeline, Category, CommandSpan, Context, Result, Syntax, EMPTY};
+use nom::{bytes::complete::take_till, error::ErrorKind, Err};
 
-pub(crate) fn category(input: &str) -> Result<CommandSpan> {
-  let (input, _) = identifier(input)?;
-  let span = CommandSpan::Category(input);
+const BLUR: &str = "#blur";
 
-  Ok((EMPTY, span))
-}
-
-fn identifier(input: &str) -> Result {
-  let (input, _) = tag("분류:")(input)?;
+pub(crate) fn category<'a>(input: &'a str, context: Context) -> Result<'a, CommandSpan<'a>> {
+  let (input, _) = context.expect(input, Syntax::Category)?;
+  let (sort_key, title) = take_till(|character| character == '|')(input)?;
+  let (title, blur) = match title.strip_suffix(BLUR) {
+    Some(title) => (title, true),
+    None => (title, false),
+  };
+  let namespaces = context.namespaces();
+  let title = namespaces.title(title);
+  if !title.is_in(namespaces.category_namespace()) {
+    return Err(Err::Error((input, ErrorKind::Verify)));
+  }
+  let sort_key = match pipeline(sort_key) {
+    "" => None,
+    sort_key => Some(sort_key.into()),
+  };
+  let span = CommandSpan::Category(Category {
+    title,
+    sort_key,
+    blur,
+  });
 
-  Ok((input, ()))
+  Ok((EMPTY, span))
 }
 
 #[cfg(test)]
@@ -23,7 +37,11 @@ mod tests {
     let source = "[[분류:]]";
     assert_eq!(
       span_list(source),
-      vec![Span::Command(CommandSpan::Category(""))]
+      vec![Span::Command(CommandSpan::Category(Category {
+        title: PageTitle::new(Some("분류"), ""),
+        sort_key: None,
+        blur: false
+      }))]
     )
   }
 
@@ -32,7 +50,70 @@ mod tests {
     let source = "[[분류:foo]]";
     assert_eq!(
       span_list(source),
-      vec![Span::Command(CommandSpan::Category("foo"))]
+      vec![Span::Command(CommandSpan::Category(Category {
+        title: PageTitle::new(Some("분류"), "foo"),
+        sort_key: None,
+        blur: false
+      }))]
+    )
+  }
+
+  #[test]
+  fn alias() {
+    let source = "[[Category:foo]]";
+    assert_eq!(
+      span_list(source),
+      vec![Span::Command(CommandSpan::Category(Category {
+        title: PageTitle::new(Some("분류"), "foo"),
+        sort_key: None,
+        blur: false
+      }))]
+    )
+  }
+
+  #[test]
+  fn custom_namespace() {
+    let options = ParseOptions::new().namespaces(NamespaceRegistry::new().category("Category"));
+    assert_eq!(
+      parse_with("[[Category:foo]]", &options),
+      vec![Block::Multiline(MultilineBlock::Paragraph(vec![
+        Span::Command(CommandSpan::Category(Category {
+          title: PageTitle::new(Some("Category"), "foo"),
+          sort_key: None,
+          blur: false
+        }))
+      ]))]
+    );
+    assert_eq!(
+      parse_with("[[분류:foo]]", &options),
+      vec![Block::Multiline(MultilineBlock::Paragraph(vec![
+        Span::Command(CommandSpan::Link(vec![], "분류:foo".into(), None))
+      ]))]
+    );
+  }
+
+  #[test]
+  fn blur_and_sort_key() {
+    let source = "[[분류:foo#blur]][[분류:bar|가나다]][[분류:baz#blur|]]";
+    assert_eq!(
+      span_list(source),
+      vec![
+        Span::Command(CommandSpan::Category(Category {
+          title: PageTitle::new(Some("분류"), "foo"),
+          sort_key: None,
+          blur: true
+        })),
+        Span::Command(CommandSpan::Category(Category {
+          title: PageTitle::new(Some("분류"), "bar"),
+          sort_key: Some("가나다".into()),
+          blur: false
+        })),
+        Span::Command(CommandSpan::Category(Category {
+          title: PageTitle::new(Some("분류"), "baz"),
+          sort_key: None,
+          blur: true
+        }))
+      ]
     )
   }
 }
@@ -1,33 +1,34 @@
-use crate::{pipeline, CommandSpan, ImageOption, Result, EMPTY};
-use nom::bytes::complete::{tag, take_till};
+use crate::{
+  pipeline, CommandSpan, Context, ImageOption, ImageRendering, ObjectFit, PageTitle, Result,
+  Syntax, Theme, EMPTY,
+};
+use nom::{bytes::complete::take_till, error::ErrorKind, Err};
 
-// TODO(Danuel): [[:파일:]] 문법 추가 필요
-pub(crate) fn image(input: &str) -> Result<CommandSpan> {
-  let (input, _) = identifier(input)?;
-  let (input, url) = url(input)?;
+pub(crate) fn image<'a>(input: &'a str, context: Context) -> Result<'a, CommandSpan<'a>> {
+  let (input, _) = context.expect(input, Syntax::Image)?;
+  let (input, title) = title(input, context)?;
   let option = option(input);
-  let span = CommandSpan::Image(url, option);
+  let span = CommandSpan::Image(title, option);
 
   Ok((EMPTY, span))
 }
 
-fn identifier(input: &str) -> Result {
-  let (input, _) = tag("파일:")(input)?;
-
-  Ok((input, ()))
-}
-
-fn url(input: &str) -> Result<&str> {
-  let (input, url) = take_till(|character| character == '|')(input)?;
+fn title<'a>(input: &'a str, context: Context) -> Result<'a, PageTitle<'a>> {
+  let (input, title) = take_till(|character| character == '|')(input)?;
+  let namespaces = context.namespaces();
+  let title = namespaces.title(title);
+  if !title.is_in(namespaces.file_namespace()) {
+    return Err(Err::Error((input, ErrorKind::Verify)));
+  }
 
-  Ok((pipeline(input), url))
+  Ok((pipeline(input), title))
 }
 
 fn option(input: &str) -> ImageOption {
   let mut option: ImageOption = Default::default();
 
   for token in input.split('&') {
-    let token_list: Vec<&str> = token.split('=').collect();
+    let token_list: Vec<&str> = token.splitn(2, '=').map(str::trim).collect();
     match token_list[..] {
       ["width", value] => {
         option.width = value.into();
@@ -38,8 +39,20 @@ fn option(input: &str) -> ImageOption {
       ["align", value] => {
         option.align = value.into();
       }
-      ["background_color", value] => {
-        option.background_color = value.into();
+      ["bgcolor", value] | ["background_color", value] => {
+        option.background_color = Some(value.into());
+      }
+      ["theme", value] => {
+        option.theme = Theme::parse(value);
+      }
+      ["border-radius", value] => {
+        option.border_radius = Some(value.into());
+      }
+      ["object-fit", value] => {
+        option.object_fit = ObjectFit::parse(value);
+      }
+      ["rendering", value] => {
+        option.rendering = ImageRendering::parse(value);
       }
       _ => {}
     }
@@ -58,7 +71,19 @@ mod tests {
     assert_eq!(
       span_list(source),
       vec![Span::Command(CommandSpan::Image(
-        "a.jpg",
+        PageTitle::new(Some("파일"), "a.jpg"),
+        Default::default()
+      ))]
+    );
+  }
+
+  #[test]
+  fn alias() {
+    let source = "[[File:a.jpg]]";
+    assert_eq!(
+      span_list(source),
+      vec![Span::Command(CommandSpan::Image(
+        PageTitle::new(Some("파일"), "a.jpg"),
         Default::default()
       ))]
     );
@@ -70,7 +95,7 @@ mod tests {
     assert_eq!(
       span_list(source),
       vec![Span::Command(CommandSpan::Image(
-        "a.jpg",
+        PageTitle::new(Some("파일"), "a.jpg"),
         ImageOption {
           width: Size::Numeric(200.0),
           ..Default::default()
@@ -85,7 +110,7 @@ mod tests {
     assert_eq!(
       span_list(source),
       vec![Span::Command(CommandSpan::Image(
-        "a.jpg",
+        PageTitle::new(Some("파일"), "a.jpg"),
         ImageOption {
           height: Size::Numeric(200.0),
           ..Default::default()
@@ -100,7 +125,7 @@ mod tests {
     assert_eq!(
       span_list(source),
       vec![Span::Command(CommandSpan::Image(
-        "a.jpg",
+        PageTitle::new(Some("파일"), "a.jpg"),
         ImageOption {
           height: Size::Pixel(200.0),
           ..Default::default()
@@ -115,7 +140,7 @@ mod tests {
     assert_eq!(
       span_list(source),
       vec![Span::Command(CommandSpan::Image(
-        "a.jpg",
+        PageTitle::new(Some("파일"), "a.jpg"),
         ImageOption {
           align: Alignment::Start,
           ..Default::default()
@@ -130,7 +155,7 @@ mod tests {
     assert_eq!(
       span_list(source),
       vec![Span::Command(CommandSpan::Image(
-        "a.jpg",
+        PageTitle::new(Some("파일"), "a.jpg"),
         ImageOption {
           align: Alignment::End,
           ..Default::default()
@@ -145,7 +170,7 @@ mod tests {
     assert_eq!(
       span_list(source),
       vec![Span::Command(CommandSpan::Image(
-        "a.jpg",
+        PageTitle::new(Some("파일"), "a.jpg"),
         ImageOption {
           align: Alignment::Left,
           ..Default::default()
@@ -160,7 +185,7 @@ mod tests {
     assert_eq!(
       span_list(source),
       vec![Span::Command(CommandSpan::Image(
-        "a.jpg",
+        PageTitle::new(Some("파일"), "a.jpg"),
         ImageOption {
           align: Alignment::Center,
           ..Default::default()
@@ -175,7 +200,7 @@ mod tests {
     assert_eq!(
       span_list(source),
       vec![Span::Command(CommandSpan::Image(
-        "a.jpg",
+        PageTitle::new(Some("파일"), "a.jpg"),
         ImageOption {
           align: Alignment::Right,
           ..Default::default()
@@ -186,13 +211,13 @@ mod tests {
 
   #[test]
   fn background_hex() {
-    let source = "[[파일:a.jpg|background=#000000]]";
+    let source = "[[파일:a.jpg|bgcolor=#ff8000]]";
     assert_eq!(
       span_list(source),
       vec![Span::Command(CommandSpan::Image(
-        "a.jpg",
+        PageTitle::new(Some("파일"), "a.jpg"),
         ImageOption {
-          background_color: Color::Hex(0, 0, 0),
+          background_color: Some(Color::Hex(255, 128, 0)),
           ..Default::default()
         }
       ))]
@@ -205,7 +230,7 @@ mod tests {
     assert_eq!(
       span_list(source),
       vec![Span::Command(CommandSpan::Image(
-        "a.jpg",
+        PageTitle::new(Some("파일"), "a.jpg"),
         ImageOption {
           width: Size::Numeric(200.0),
           height: Size::Numeric(200.0),
@@ -221,7 +246,7 @@ mod tests {
     assert_eq!(
       span_list(source),
       vec![Span::Command(CommandSpan::Image(
-        "a.jpg",
+        PageTitle::new(Some("파일"), "a.jpg"),
         ImageOption {
           width: Size::Numeric(200.0),
           height: Size::Numeric(200.0),
@@ -230,4 +255,52 @@ mod tests {
       ))]
     );
   }
+
+  #[test]
+  fn background_name() {
+    let source = "[[파일:a.jpg|bgcolor=red]]";
+    assert_eq!(
+      span_list(source),
+      vec![Span::Command(CommandSpan::Image(
+        PageTitle::new(Some("파일"), "a.jpg"),
+        ImageOption {
+          background_color: Some(Color::Raw("red".into())),
+          ..Default::default()
+        }
+      ))]
+    );
+  }
+
+  #[test]
+  fn full_option() {
+    let source =
+      "[[파일:a.jpg|width=50%&theme=dark&border-radius=4px&object-fit=cover&rendering=pixelated]]";
+    assert_eq!(
+      span_list(source),
+      vec![Span::Command(CommandSpan::Image(
+        PageTitle::new(Some("파일"), "a.jpg"),
+        ImageOption {
+          width: Size::Percent(50.0),
+          theme: Some(Theme::Dark),
+          border_radius: Some(Size::Pixel(4.0)),
+          object_fit: Some(ObjectFit::Cover),
+          rendering: Some(ImageRendering::Pixelated),
+          ..Default::default()
+        }
+      ))]
+    );
+  }
+
+  #[test]
+  fn link_to_file() {
+    let source = "[[:파일:a.jpg]]";
+    assert_eq!(
+      span_list(source),
+      vec![Span::Command(CommandSpan::Link(
+        vec![],
+        ":파일:a.jpg".into(),
+        None
+      ))]
+    );
+  }
 }
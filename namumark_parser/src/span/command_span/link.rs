@@ -1,19 +1,56 @@
-use crate::{pipeline, span_list, CommandSpan, Result, EMPTY};
-use nom::bytes::complete::take_till;
+use crate::{
+  lexer::{find, find_unescaped, unescape},
+  pipeline, span_list_with, CommandSpan, Context, Result, Syntax, EMPTY,
+};
+use std::borrow::Cow;
 
-pub(crate) fn link(input: &str) -> Result<CommandSpan> {
-  let (input, link) = start(input)?;
-  let span_list = span_list(input);
-  let span = CommandSpan::Link(span_list, link);
+const ANCHOR: &[u8] = b"#";
+const EXTERNAL_LINK: &str = "://";
+
+pub(crate) fn link<'a>(input: &'a str, context: Context) -> Result<'a, CommandSpan<'a>> {
+  let (input, _) = context.expect(input, Syntax::Link)?;
+  let (input, (link, anchor)) = start(input, context.is_enabled(Syntax::Escape))?;
+  let span_list = span_list_with(input, context);
+  let span = CommandSpan::Link(span_list, link, anchor);
 
   Ok((EMPTY, span))
 }
 
-fn start(input: &str) -> Result<&str> {
-  let (input, link) = take_till(|character| character == '|')(input)?;
+/// `[[:파일:a.jpg]]`, `[[:분류:foo]]`처럼 앞에 `:`을 붙이면 이미지나 분류 대신 문서 링크가 된다.
+/// 원래 문법으로 되돌릴 수 있도록 `:`은 대상에 남긴다
+/// 이스케이프하면 대상에 `|`나 `]`를 쓸 수 있고, 대상에서는 `\\`를 떼어 낸다.
+/// `\\#`이 문단 이름의 시작으로 읽히지 않도록 이스케이프를 풀기 전에 `#` 뒤를 나눈다
+fn start<'a>(
+  input: &'a str,
+  skip_escaped: bool,
+) -> Result<'a, (Cow<'a, str>, Option<Cow<'a, str>>)> {
+  let index = if skip_escaped {
+    find_unescaped(input.as_bytes(), b"|")
+  } else {
+    find(input.as_bytes(), b"|")
+  };
+  let (link, input) = input.split_at(index.unwrap_or(input.len()));
   let input = pipeline(input);
+  let anchor_index = if link.contains(EXTERNAL_LINK) {
+    None
+  } else if skip_escaped {
+    find_unescaped(link.as_bytes(), ANCHOR)
+  } else {
+    find(link.as_bytes(), ANCHOR)
+  };
+  let (link, anchor) = match anchor_index {
+    Some(index) => (&link[..index], Some(&link[index + ANCHOR.len()..])),
+    None => (link, None),
+  };
+  let text = |text: &'a str| -> Cow<'a, str> {
+    if skip_escaped {
+      unescape(text)
+    } else {
+      text.into()
+    }
+  };
 
-  Ok((input, link))
+  Ok((input, (text(link), anchor.map(text))))
 }
 
 #[cfg(test)]
@@ -25,7 +62,7 @@ mod tests {
     let source = "[[]]";
     assert_eq!(
       span_list(source),
-      vec![Span::Command(CommandSpan::Link(vec![], ""))]
+      vec![Span::Command(CommandSpan::Link(vec![], "".into(), None))]
     );
   }
 
@@ -34,7 +71,54 @@ mod tests {
     let source = "[[/foo]]";
     assert_eq!(
       span_list(source),
-      vec![Span::Command(CommandSpan::Link(vec![], "/foo"))]
+      vec![Span::Command(CommandSpan::Link(
+        vec![],
+        "/foo".into(),
+        None
+      ))]
+    );
+  }
+
+  #[test]
+  fn escape() {
+    assert_eq!(
+      span_list("[[a|b\\]]c]]"),
+      vec![Span::Command(CommandSpan::Link(
+        vec![
+          Span::Inline("b".into()),
+          Span::Escaped(']'),
+          Span::Inline("]c".into())
+        ],
+        "a".into(),
+        None
+      ))]
+    );
+    assert_eq!(
+      span_list("[[a\\|b]]"),
+      vec![Span::Command(CommandSpan::Link(vec![], "a|b".into(), None))]
+    );
+    assert_eq!(
+      span_list("[[a\\\\|b]]"),
+      vec![Span::Command(CommandSpan::Link(
+        vec![Span::Inline("b".into())],
+        "a\\".into(),
+        None
+      ))]
+    );
+  }
+
+  #[test]
+  fn disable_escape() {
+    let options = ParseOptions::new().disable(Syntax::Escape);
+    assert_eq!(
+      parse_with("[[a\\|b]]", &options),
+      vec![Block::Multiline(MultilineBlock::Paragraph(vec![
+        Span::Command(CommandSpan::Link(
+          vec![Span::Inline("b".into())],
+          "a\\".into(),
+          None
+        ))
+      ]))]
     );
   }
 
@@ -44,8 +128,49 @@ mod tests {
     assert_eq!(
       span_list(source),
       vec![Span::Command(CommandSpan::Link(
-        vec![Span::Inline("Danuel")],
-        "/foo"
+        vec![Span::Inline("Danuel".into())],
+        "/foo".into(),
+        None
+      ))]
+    );
+  }
+
+  #[test]
+  fn anchor() {
+    assert_eq!(
+      span_list("[[a#s-1]]"),
+      vec![Span::Command(CommandSpan::Link(
+        vec![],
+        "a".into(),
+        Some("s-1".into())
+      ))]
+    );
+    assert_eq!(
+      span_list("[[#s-1|b]]"),
+      vec![Span::Command(CommandSpan::Link(
+        vec![Span::Inline("b".into())],
+        "".into(),
+        Some("s-1".into())
+      ))]
+    );
+    assert_eq!(
+      span_list("[[C\\#]]"),
+      vec![Span::Command(CommandSpan::Link(vec![], "C#".into(), None))]
+    );
+    assert_eq!(
+      span_list("[[C\\##a\\#b]]"),
+      vec![Span::Command(CommandSpan::Link(
+        vec![],
+        "C#".into(),
+        Some("a#b".into())
+      ))]
+    );
+    assert_eq!(
+      span_list("[[https://example.com/#a]]"),
+      vec![Span::Command(CommandSpan::Link(
+        vec![],
+        "https://example.com/#a".into(),
+        None
       ))]
     );
   }
@@ -3,42 +3,86 @@ mod image;
 mod link;
 mod video;
 
-use crate::{line, Alignment, Color, Result, Size, Span};
+use crate::{
+  lexer::{find, find_unescaped},
+  percent_encode, Alignment, Color, Context, PageTitle, Result, Size, Span, Syntax,
+};
 use category::category;
 use image::image;
 use link::link;
-use nom::{
-  branch::alt,
-  bytes::complete::{tag, take_until},
-};
+use nom::{branch::alt, bytes::complete::tag, error::ErrorKind, Err};
+use std::borrow::Cow;
 use video::video;
 
 #[derive(Debug, PartialEq)]
 pub enum CommandSpan<'a> {
-  Category(&'a str),
-  Image(&'a str, ImageOption<'a>),
+  Category(Category<'a>),
+  Image(PageTitle<'a>, ImageOption<'a>),
   /// parent link와 child link는 아래 2개 케이스에 대해서만 작동한다
   /// parent => ../
   /// child => /foo
-  Link(Vec<Span<'a>>, &'a str),
-  Video(&'a str, VideoOption),
+  /// 대상과 `#` 뒤의 문단 이름. 외부 링크는 `#`을 나누지 않는다
+  Link(Vec<Span<'a>>, Cow<'a, str>, Option<Cow<'a, str>>),
+  /// 영상 ID. `html5video`는 영상 주소
+  Video(Cow<'a, str>, VideoOption),
+}
+
+/// `[[분류:이름#blur|정렬 키]]`
+#[derive(Debug, Clone, PartialEq)]
+pub struct Category<'a> {
+  pub title: PageTitle<'a>,
+  /// 분류 문서에서 이 문서를 정렬할 때 제목 대신 사용하는 값
+  pub sort_key: Option<Cow<'a, str>>,
+  /// `#blur`가 붙으면 분류 문서에서 이 문서를 흐리게 보여준다
+  pub blur: bool,
 }
 
 #[derive(Debug, Default, PartialEq)]
 pub struct ImageOption<'a> {
-  width: Size,
-  height: Size,
-  align: Alignment,
-  background_color: Color<'a>,
+  pub width: Size,
+  pub height: Size,
+  pub align: Alignment,
+  pub background_color: Option<Color<'a>>,
+  /// 지정한 테마에서만 이미지를 보여준다
+  pub theme: Option<Theme>,
+  pub border_radius: Option<Size>,
+  pub object_fit: Option<ObjectFit>,
+  pub rendering: Option<ImageRendering>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Theme {
+  Light,
+  Dark,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ObjectFit {
+  Fill,
+  Contain,
+  Cover,
+  None,
+  ScaleDown,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ImageRendering {
+  Auto,
+  Smooth,
+  HighQuality,
+  CrispEdges,
+  Pixelated,
 }
 
 #[derive(Debug, Default, PartialEq)]
 pub struct VideoOption {
-  platform: VideoPlatform,
-  width: Size,
-  height: Size,
-  start: u32,
-  end: u32,
+  pub platform: VideoPlatform,
+  pub width: Size,
+  pub height: Size,
+  /// 초 단위
+  pub start: Option<u32>,
+  /// 초 단위
+  pub end: Option<u32>,
 }
 
 #[derive(Debug, PartialEq)]
@@ -46,6 +90,9 @@ pub enum VideoPlatform {
   Youtube,
   KakaoTv,
   NicoVideo,
+  Vimeo,
+  NaverTv,
+  Html5,
 }
 
 impl Default for VideoPlatform {
@@ -54,28 +101,144 @@ impl Default for VideoPlatform {
   }
 }
 
-pub(crate) fn command_span(input: &str) -> Result<CommandSpan> {
+impl Theme {
+  pub fn parse(source: &str) -> Option<Self> {
+    match source {
+      "light" => Some(Theme::Light),
+      "dark" => Some(Theme::Dark),
+      _ => None,
+    }
+  }
+}
+
+impl ObjectFit {
+  pub fn parse(source: &str) -> Option<Self> {
+    match source {
+      "fill" => Some(ObjectFit::Fill),
+      "contain" => Some(ObjectFit::Contain),
+      "cover" => Some(ObjectFit::Cover),
+      "none" => Some(ObjectFit::None),
+      "scale-down" => Some(ObjectFit::ScaleDown),
+      _ => None,
+    }
+  }
+}
+
+impl ImageRendering {
+  pub fn parse(source: &str) -> Option<Self> {
+    match source {
+      "auto" => Some(ImageRendering::Auto),
+      "smooth" => Some(ImageRendering::Smooth),
+      "high-quality" => Some(ImageRendering::HighQuality),
+      "crisp-edges" => Some(ImageRendering::CrispEdges),
+      "pixelated" => Some(ImageRendering::Pixelated),
+      _ => None,
+    }
+  }
+}
+
+impl VideoOption {
+  /// 렌더러가 `<iframe>`이나 `<video>`에 넣을 주소. 영상 ID는 주소에 맞게 인코딩한다.
+  /// `html5video` 주소가 `http`나 `https`가 아닌 스킴을 쓰면 `None`이다
+  pub fn embed_url(&self, source: &str) -> Option<String> {
+    if self.platform == VideoPlatform::Html5 && !is_http_source(source) {
+      return None;
+    }
+    let query = |start_key: &str, end_key: Option<&str>| {
+      let mut parameter_list = vec![];
+      if let Some(start) = self.start {
+        parameter_list.push(format!("{}={}", start_key, start));
+      }
+      if let (Some(end), Some(end_key)) = (self.end, end_key) {
+        parameter_list.push(format!("{}={}", end_key, end));
+      }
+      if parameter_list.is_empty() {
+        String::new()
+      } else {
+        format!("?{}", parameter_list.join("&"))
+      }
+    };
+
+    let id = percent_encode(source);
+    let url = match self.platform {
+      VideoPlatform::Youtube => format!(
+        "https://www.youtube.com/embed/{}{}",
+        id,
+        query("start", Some("end"))
+      ),
+      VideoPlatform::KakaoTv => format!(
+        "https://tv.kakao.com/embed/player/cliplink/{}{}",
+        id,
+        query("start", None)
+      ),
+      VideoPlatform::NicoVideo => format!(
+        "https://embed.nicovideo.jp/watch/{}{}",
+        id,
+        query("from", None)
+      ),
+      VideoPlatform::Vimeo => match self.start {
+        Some(start) => format!("https://player.vimeo.com/video/{}#t={}s", id, start),
+        None => format!("https://player.vimeo.com/video/{}", id),
+      },
+      VideoPlatform::NaverTv => format!("https://tv.naver.com/embed/{}", id),
+      VideoPlatform::Html5 => match (self.start, self.end) {
+        (None, None) => source.to_owned(),
+        (start, end) => format!(
+          "{}#t={}{}",
+          source,
+          start.unwrap_or(0),
+          end.map(|end| format!(",{}", end)).unwrap_or_default()
+        ),
+      },
+    };
+
+    Some(url)
+  }
+}
+
+/// 스킴이 없거나 `http`, `https`인 주소인지. 브라우저가 스킴에서 무시하는 공백과 제어 문자가 있으면 받지 않는다
+pub(crate) fn is_http_source(source: &str) -> bool {
+  if source
+    .chars()
+    .any(|character| character.is_whitespace() || character.is_control())
+  {
+    return false;
+  }
+  match source.find(|character| matches!(character, ':' | '/' | '?' | '#')) {
+    Some(index) if source.as_bytes()[index] == b':' => {
+      let scheme = &source[..index];
+      scheme.eq_ignore_ascii_case("http") || scheme.eq_ignore_ascii_case("https")
+    }
+    _ => true,
+  }
+}
+
+pub(crate) fn command_span<'a>(input: &'a str, context: Context) -> Result<'a, CommandSpan<'a>> {
   fn start(input: &str) -> Result {
     let (input, _) = tag("[[")(input)?;
 
     Ok((input, ()))
   };
 
-  fn end(input: &str) -> Result<&str> {
-    let (_, line) = take_until("]]")(input)?;
+  fn end(input: &str, skip_escaped: bool) -> Result<&str> {
+    let index = if skip_escaped {
+      find_unescaped(input.as_bytes(), b"]]")
+    } else {
+      find(input.as_bytes(), b"]]")
+    };
+    let index = index.ok_or(Err::Error((input, ErrorKind::TakeUntil)))?;
 
-    Ok((&input[line.len() + 2..], line))
+    Ok((&input[index + 2..], &input[..index]))
   };
 
   let (input, _) = start(input)?;
-  let (input, line) = end(input)?;
-  let (_, span) = alt((image, video, category, link))(line)?;
+  let (input, line) = end(input, context.is_enabled(Syntax::Escape))?;
+  let (_, span) = alt((
+    |input| image(input, context),
+    |input| video(input, context),
+    |input| category(input, context),
+    |input| link(input, context),
+  ))(line)?;
 
   Ok((input, span))
 }
-
-pub(crate) fn starts_with_command_span(input: &str) -> bool {
-  let (_, input) = line(input);
-
-  input.starts_with("[[") && input.find("]]").is_some()
-}
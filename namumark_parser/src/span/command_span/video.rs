@@ -1,16 +1,33 @@
-use crate::{CommandSpan, Result, VideoOption, VideoPlatform, EMPTY};
-use nom::{
-  branch::alt,
-  bytes::complete::{tag, take_till, take_until},
-  character::complete::char,
-  combinator::all_consuming,
-};
-
-pub(crate) fn video(input: &str) -> Result<CommandSpan> {
+use super::super::macro_span::arguments;
+use super::is_http_source;
+use crate::{CommandSpan, Context, Result, Syntax, VideoOption, VideoPlatform, EMPTY};
+use nom::{branch::alt, bytes::complete::tag, error::ErrorKind, Err};
+
+pub(crate) fn video<'a>(input: &'a str, context: Context) -> Result<'a, CommandSpan<'a>> {
+  let (input, _) = context.expect(input, Syntax::Video)?;
   let (input, platform) = identifier(input)?;
-  let (input, _) = parens(input)?;
-  let (url, option) = center(input, platform)?;
-  let span = CommandSpan::Video(url, option);
+  let (_, arguments) = arguments(input)?;
+  let mut option = VideoOption {
+    platform,
+    ..Default::default()
+  };
+  for (key, value) in arguments.named_iter() {
+    match key {
+      "width" => option.width = value.into(),
+      "height" => option.height = value.into(),
+      "start" => option.start = seconds(value),
+      "end" => option.end = seconds(value),
+      _ => {}
+    }
+  }
+  let (mut positional_list, _) = arguments.into_parts();
+  if positional_list.is_empty() || positional_list[0].is_empty() {
+    return Err(Err::Error((input, ErrorKind::Verify)));
+  }
+  if option.platform == VideoPlatform::Html5 && !is_http_source(&positional_list[0]) {
+    return Err(Err::Error((input, ErrorKind::Verify)));
+  }
+  let span = CommandSpan::Video(positional_list.swap_remove(0), option);
 
   Ok((EMPTY, span))
 }
@@ -29,48 +46,36 @@ fn identifier(input: &str) -> Result<VideoPlatform> {
   platform!("youtube", youtube, Youtube);
   platform!("kakaotv", kakaotv, KakaoTv);
   platform!("nicovideo", nicovideo, NicoVideo);
+  platform!("vimeo", vimeo, Vimeo);
+  platform!("navertv", navertv, NaverTv);
+  platform!("html5video", html5video, Html5);
 
-  let (input, platform) = alt((youtube, kakaotv, nicovideo))(input)?;
-  let (input, _) = char('(')(input)?;
-
-  Ok((input, platform))
+  alt((youtube, kakaotv, nicovideo, vimeo, navertv, html5video))(input)
 }
 
-fn center(input: &str, platform: VideoPlatform) -> Result<VideoOption> {
-  let (input, url) = take_till(|character| character == ',')(input)?;
-  let mut option = VideoOption {
-    platform,
-    ..Default::default()
-  };
-
-  for token in input.split(',') {
-    let token_list: Vec<&str> = token.split('=').collect();
-    match token_list[..] {
-      ["width", value] => {
-        option.width = value.into();
-      }
-      ["height", value] => {
-        option.height = value.into();
+/// `90`, `1:30`, `1:01:30` 형식. 올바르지 않은 값은 무시한다
+fn seconds(input: &str) -> Option<u32> {
+  let mut seconds: u32 = 0;
+  let mut count = 0;
+  for token in input.trim().split(':') {
+    let value: u32 = match token.trim() {
+      token if !token.is_empty() && token.bytes().all(|byte| byte.is_ascii_digit()) => {
+        token.parse().ok()?
       }
-      ["start", value] => {
-        option.start = value.parse().unwrap();
-      }
-      ["end", value] => {
-        option.end = value.parse().unwrap();
-      }
-      _ => {}
+      _ => return None,
+    };
+    if count > 0 && value >= 60 {
+      return None;
     }
+    seconds = seconds.checked_mul(60)?.checked_add(value)?;
+    count += 1;
   }
 
-  Ok((url, option))
-}
-
-fn parens(input: &str) -> Result {
-  let (end_input, input) = take_until(")")(input)?;
-  let (end_input, _) = char(')')(end_input)?;
-  let _ = all_consuming(|input: &str| -> Result { Ok((input, ())) })(end_input);
-
-  Ok((input, ()))
+  if count <= 3 {
+    Some(seconds)
+  } else {
+    None
+  }
 }
 
 #[cfg(test)]
@@ -83,7 +88,7 @@ mod tests {
     assert_eq!(
       span_list(source),
       vec![Span::Command(CommandSpan::Video(
-        "danuel",
+        "danuel".into(),
         VideoOption {
           platform: VideoPlatform::Youtube,
           ..Default::default()
@@ -98,7 +103,7 @@ mod tests {
     assert_eq!(
       span_list(source),
       vec![Span::Command(CommandSpan::Video(
-        "danuel",
+        "danuel".into(),
         VideoOption {
           platform: VideoPlatform::KakaoTv,
           ..Default::default()
@@ -113,7 +118,7 @@ mod tests {
     assert_eq!(
       span_list(source),
       vec![Span::Command(CommandSpan::Video(
-        "danuel",
+        "danuel".into(),
         VideoOption {
           platform: VideoPlatform::NicoVideo,
           ..Default::default()
@@ -121,4 +126,131 @@ mod tests {
       ))]
     );
   }
+
+  #[test]
+  fn more_platforms() {
+    let platform_list = vec![
+      ("vimeo", VideoPlatform::Vimeo),
+      ("navertv", VideoPlatform::NaverTv),
+      ("html5video", VideoPlatform::Html5),
+    ];
+    for (identifier, platform) in platform_list {
+      let source = format!("[[{}(danuel)]]", identifier);
+      assert_eq!(
+        span_list(&source),
+        vec![Span::Command(CommandSpan::Video(
+          "danuel".into(),
+          VideoOption {
+            platform,
+            ..Default::default()
+          }
+        ))]
+      );
+    }
+  }
+
+  #[test]
+  fn option() {
+    let source = "[[youtube(danuel, width=640, height = 360px, start=1:30, end=200)]]";
+    assert_eq!(
+      span_list(source),
+      vec![Span::Command(CommandSpan::Video(
+        "danuel".into(),
+        VideoOption {
+          platform: VideoPlatform::Youtube,
+          width: Size::Numeric(640.0),
+          height: Size::Pixel(360.0),
+          start: Some(90),
+          end: Some(200),
+        }
+      ))]
+    );
+  }
+
+  #[test]
+  fn invalid_time() {
+    let source = "[[youtube(danuel, start=foo, end=1:99)]]";
+    assert_eq!(
+      span_list(source),
+      vec![Span::Command(CommandSpan::Video(
+        "danuel".into(),
+        VideoOption {
+          platform: VideoPlatform::Youtube,
+          ..Default::default()
+        }
+      ))]
+    );
+  }
+
+  #[test]
+  fn embed_url() {
+    let option = |platform, start, end| VideoOption {
+      platform,
+      start,
+      end,
+      ..Default::default()
+    };
+    assert_eq!(
+      option(VideoPlatform::Youtube, Some(90), Some(100)).embed_url("abc"),
+      Some("https://www.youtube.com/embed/abc?start=90&end=100".to_owned())
+    );
+    assert_eq!(
+      option(VideoPlatform::Youtube, None, None).embed_url("abc"),
+      Some("https://www.youtube.com/embed/abc".to_owned())
+    );
+    assert_eq!(
+      option(VideoPlatform::NicoVideo, Some(5), None).embed_url("sm9"),
+      Some("https://embed.nicovideo.jp/watch/sm9?from=5".to_owned())
+    );
+    assert_eq!(
+      option(VideoPlatform::Vimeo, Some(5), None).embed_url("1"),
+      Some("https://player.vimeo.com/video/1#t=5s".to_owned())
+    );
+    assert_eq!(
+      option(VideoPlatform::Html5, Some(5), Some(10)).embed_url("a.mp4"),
+      Some("a.mp4#t=5,10".to_owned())
+    );
+    assert_eq!(
+      option(VideoPlatform::Youtube, None, None).embed_url("a?b=c&d#e"),
+      Some("https://www.youtube.com/embed/a%3Fb%3Dc%26d%23e".to_owned())
+    );
+    assert_eq!(
+      option(VideoPlatform::KakaoTv, None, None).embed_url("../1 2"),
+      Some("https://tv.kakao.com/embed/player/cliplink/..%2F1%202".to_owned())
+    );
+    assert_eq!(
+      option(VideoPlatform::Html5, None, None).embed_url("HTTPS://a.com/a.mp4"),
+      Some("HTTPS://a.com/a.mp4".to_owned())
+    );
+    assert_eq!(
+      option(VideoPlatform::Html5, None, None).embed_url("javascript:alert(1)"),
+      None
+    );
+    assert_eq!(
+      option(VideoPlatform::Html5, None, None).embed_url("java\tscript:alert(1)"),
+      None
+    );
+  }
+
+  #[test]
+  fn html5_scheme() {
+    assert_eq!(
+      span_list("[[html5video(https://a.com/a.mp4)]]"),
+      vec![Span::Command(CommandSpan::Video(
+        "https://a.com/a.mp4".into(),
+        VideoOption {
+          platform: VideoPlatform::Html5,
+          ..Default::default()
+        }
+      ))]
+    );
+    for source in &[
+      "[[html5video(javascript:alert)]]",
+      "[[html5video(data:video/mp4;base64,AAAA)]]",
+    ] {
+      assert!(!span_list(source)
+        .iter()
+        .any(|span| matches!(span, Span::Command(CommandSpan::Video(..)))));
+    }
+  }
 }
@@ -1,40 +1,377 @@
 use super::{
-  bracket_span::starts_with_bracket_span, command_span::starts_with_command_span,
-  macro_span::starts_with_macro_span, semantic_span::starts_with_sematic_span,
+  bracket_span::{bracket_pair_list, bracket_span},
+  command_span::command_span,
+  macro_span::macro_body,
+  semantic_span::{semantic_span, DelimiterPair, DelimiterStack},
 };
-use crate::Result;
-
-pub(crate) fn inline(input: &str) -> Result<&str> {
-  let mut index = 0;
-  while index < input.len() {
-    if let Some(slice) = input.get(index..) {
-      if starts_with_span(slice) {
-        break;
+use crate::{
+  lexer::{Finder, Lexer},
+  Context, SemanticSpan, Span, Syntax,
+};
+use std::{iter::Peekable, vec::IntoIter};
+
+/// 입력을 한 번 훑으면서 표시가 아닌 스팬과 강조 표시의 짝을 모은 뒤, 짝을 따라 트리를 만든다.
+/// 스팬이 시작될 수 있는 위치는 `Lexer`가, 닫는 `]]`와 `]`는 `Finder`가, `{{{`의 짝은 미리 만든 표가 찾으므로
+/// 입력 길이에 비례하는 시간이 걸리고, 표시가 아닌 글자는 한 바이트씩 보지 않고 건너뛴다
+pub(crate) fn span_list<'a>(input: &'a str, context: Context) -> Vec<Span<'a>> {
+  let mut lexer = Lexer::new(input);
+  let mut scanner = Scanner::new(input, context);
+  let mut delimiter_stack = DelimiterStack::default();
+  let mut atom_list = vec![];
+  let mut from = 0;
+
+  // 모든 표시가 ASCII이므로 후보는 늘 글자 경계에 있다
+  while let Some(index) = lexer.next_candidate(from) {
+    let marker = input.as_bytes()[index];
+    if b"'~-_^,".contains(&marker) {
+      let end = input.as_bytes()[index..]
+        .iter()
+        .position(|byte| *byte != marker)
+        .map_or(input.len(), |len| index + len);
+      delimiter_stack.push(marker, index, end, context);
+      from = end;
+      continue;
+    }
+    if marker == b'\\' && context.is_enabled(Syntax::Escape) {
+      // 줄바꿈은 이스케이프하지 않는다
+      if let Some(character) = input[index + 1..].chars().next().filter(|c| *c != '\n') {
+        let end = index + 1 + character.len_utf8();
+        atom_list.push(Atom {
+          start: index,
+          end,
+          span: Span::Escaped(character),
+        });
+        from = end;
+        continue;
+      }
+    }
+    if marker == b'\n' {
+      delimiter_stack.end_line();
+    }
+    match scanner.span(index) {
+      Some((end, span)) => {
+        atom_list.push(Atom {
+          start: index,
+          end,
+          span,
+        });
+        from = end;
       }
-      index += slice.chars().next().unwrap().len_utf8();
-    } else {
-      break;
+      None => from = index + 1,
     }
   }
-  let (input, inline) = (&input[index..], &input[..index]);
 
-  Ok((input, inline))
+  let mut builder = Builder {
+    input,
+    pair_list: delimiter_stack.into_pair_list().into_iter().peekable(),
+    atom_list: atom_list.into_iter().peekable(),
+  };
+  builder.span_list(0, input.len(), context)
 }
 
-fn starts_with_span(input: &str) -> bool {
-  starts_with_sematic_span(input)
-    || starts_with_bracket_span(input)
-    || starts_with_command_span(input)
-    || starts_with_macro_span(input)
+/// 강조 표시가 아닌 스팬과 그 범위
+struct Atom<'a> {
+  start: usize,
+  end: usize,
+  span: Span<'a>,
+}
+
+/// 짝과 스팬을 앞에서부터 꺼내 트리로 만든다. 짝은 서로 엇갈리지 않으므로 한 번씩만 꺼낸다
+struct Builder<'a> {
+  input: &'a str,
+  pair_list: Peekable<IntoIter<DelimiterPair>>,
+  atom_list: Peekable<IntoIter<Atom<'a>>>,
+}
+
+impl<'a> Builder<'a> {
+  /// `input[from..to]`의 스팬 목록
+  fn span_list(&mut self, from: usize, to: usize, context: Context) -> Vec<Span<'a>> {
+    let mut span_list = vec![];
+    let mut inline_start = from;
+    loop {
+      let pair_start = self.pair_list.peek().map(|pair| pair.open.0);
+      let atom_start = self.atom_list.peek().map(|atom| atom.start);
+      let (start, end, span) = match (pair_start, atom_start) {
+        (Some(pair_start), atom_start)
+          if pair_start < to && atom_start.map_or(true, |atom_start| pair_start < atom_start) =>
+        {
+          let pair = self.pair_list.next().unwrap();
+          let (content_start, content_end) = (pair.open.1, pair.close.0);
+          // 더 깊이 중첩할 수 없으면 안쪽을 일반 텍스트로 남긴다
+          let content = match context.nested() {
+            Some(context) => self.span_list(content_start, content_end, context),
+            None => {
+              self.skip(content_end);
+              match &self.input[content_start..content_end] {
+                "" => vec![],
+                content => vec![Span::Inline(content.into())],
+              }
+            }
+          };
+          let span = Span::Semantic(semantic_span(pair.syntax, content));
+          (pair.open.0, pair.close.1, span)
+        }
+        (_, Some(atom_start)) if atom_start < to => {
+          let atom = self.atom_list.next().unwrap();
+          (atom.start, atom.end, atom.span)
+        }
+        _ => break,
+      };
+      if inline_start < start {
+        span_list.push(Span::Inline(self.input[inline_start..start].into()));
+      }
+      span_list.push(span);
+      inline_start = end;
+    }
+    if inline_start < to {
+      span_list.push(Span::Inline(self.input[inline_start..to].into()));
+    }
+
+    span_list
+  }
+
+  /// `to` 앞에서 시작하는 짝과 스팬을 버린다
+  fn skip(&mut self, to: usize) {
+    while self.pair_list.next_if(|pair| pair.open.0 < to).is_some() {}
+    while self.atom_list.next_if(|atom| atom.start < to).is_some() {}
+  }
+}
+
+struct Scanner<'a, 'o> {
+  input: &'a str,
+  context: Context<'o>,
+  linebreak_finder: Finder,
+  command_end_finder: Finder,
+  macro_end_finder: Finder,
+  /// 인자가 있는 매크로의 끝. 인자에 `]`가 들어갈 수 있다
+  macro_arguments_end_finder: Finder,
+  /// `{`를 처음 만났을 때 만든다
+  bracket_pair_list: Option<Vec<(usize, Option<usize>)>>,
+  bracket_pair_index: usize,
+}
+
+impl<'a, 'o> Scanner<'a, 'o> {
+  fn new(input: &'a str, context: Context<'o>) -> Self {
+    Scanner {
+      input,
+      context,
+      linebreak_finder: Finder::new("\n"),
+      command_end_finder: Finder::new("]]").skip_escaped(context.is_enabled(Syntax::Escape)),
+      macro_end_finder: Finder::new("]").skip_escaped(context.is_enabled(Syntax::Escape)),
+      macro_arguments_end_finder: Finder::new(")]")
+        .skip_escaped(context.is_enabled(Syntax::Escape)),
+      bracket_pair_list: None,
+      bracket_pair_index: 0,
+    }
+  }
+
+  /// `index`에서 시작하는 스팬과 그 스팬이 끝나는 위치
+  fn span(&mut self, index: usize) -> Option<(usize, Span<'a>)> {
+    match self.input.as_bytes()[index] {
+      b'\n' => Some((index + 1, Span::Semantic(SemanticSpan::Linebreak))),
+      b'{' => self.bracket(index),
+      b'[' => self.command(index).or_else(|| self.macro_span(index)),
+      _ => None,
+    }
+  }
+
+  fn bracket(&mut self, index: usize) -> Option<(usize, Span<'a>)> {
+    if !self.context.is_enabled(Syntax::Bracket) || !self.input[index..].starts_with("{{{") {
+      return None;
+    }
+    let input = self.input;
+    let pair_list = self
+      .bracket_pair_list
+      .get_or_insert_with(|| bracket_pair_list(input));
+    while pair_list
+      .get(self.bracket_pair_index)
+      .map_or(false, |(start, _)| *start < index)
+    {
+      self.bracket_pair_index += 1;
+    }
+    match pair_list.get(self.bracket_pair_index) {
+      Some((start, Some(_))) if *start == index => {}
+      _ => return None,
+    }
+    let (rest, span) = bracket_span(&input[index..], self.context).ok()?;
+
+    Some((input.len() - rest.len(), Span::Bracket(span)))
+  }
+
+  /// 닫는 `]]`가 같은 줄에 있을 때만 시도한다
+  fn command(&mut self, index: usize) -> Option<(usize, Span<'a>)> {
+    if !self.input[index..].starts_with("[[") {
+      return None;
+    }
+    let end = self.command_end_finder.find(self.input, index)?;
+    if !self.is_same_line(index, end) {
+      return None;
+    }
+    let (rest, span) = command_span(&self.input[index..], self.context).ok()?;
+
+    Some((self.input.len() - rest.len(), Span::Command(span)))
+  }
+
+  /// 닫는 `]`가 같은 줄에 있을 때만 시도한다.
+  /// `[include(틀:a, text=[[b]])]`처럼 이름 뒤에 `(`가 오면 `)]`를 먼저 찾는다
+  fn macro_span(&mut self, index: usize) -> Option<(usize, Span<'a>)> {
+    if self.has_arguments(index) {
+      let span = self
+        .macro_arguments_end_finder
+        .find(self.input, index)
+        .and_then(|end| self.macro_span_until(index, end + 1));
+      if span.is_some() {
+        return span;
+      }
+    }
+    let end = self.macro_end_finder.find(self.input, index)?;
+    self.macro_span_until(index, end)
+  }
+
+  /// `index`의 `[`부터 `end`의 `]`까지
+  fn macro_span_until(&mut self, index: usize, end: usize) -> Option<(usize, Span<'a>)> {
+    if !self.is_same_line(index, end) {
+      return None;
+    }
+    let (_, span) = macro_body(&self.input[index + 1..end], self.context).ok()?;
+
+    Some((end + 1, Span::Macro(span)))
+  }
+
+  fn has_arguments(&self, index: usize) -> bool {
+    let body = &self.input.as_bytes()[index + 1..];
+    let name_len = body
+      .iter()
+      .position(|byte| !(byte.is_ascii_alphanumeric() || *byte == b'_'))
+      .unwrap_or(body.len());
+    name_len > 0 && body.get(name_len) == Some(&b'(')
+  }
+
+  fn is_same_line(&mut self, start: usize, end: usize) -> bool {
+    match self.linebreak_finder.find(self.input, start) {
+      Some(linebreak) => end < linebreak,
+      None => true,
+    }
+  }
 }
 
 #[cfg(test)]
 mod tests {
-  use super::super::*;
+  use crate::*;
 
   #[test]
   fn basic() {
     let source = "Danuel";
-    assert_eq!(span_list(source), vec![Span::Inline("Danuel")]);
+    assert_eq!(span_list(source), vec![Span::Inline("Danuel".into())]);
+  }
+
+  #[test]
+  fn long_line_without_span() {
+    let source = "[".repeat(1 << 20);
+    assert_eq!(
+      span_list(&source),
+      vec![Span::Inline(source.as_str().into())]
+    );
+
+    let source = "{{{".repeat(1 << 16);
+    assert_eq!(
+      span_list(&source),
+      vec![Span::Inline(source.as_str().into())]
+    );
+  }
+
+  #[test]
+  fn span_after_unclosed_marker() {
+    assert_eq!(
+      span_list("[[foo\n~~bar~~ [br]"),
+      vec![
+        Span::Inline("[[foo".into()),
+        Span::Semantic(SemanticSpan::Linebreak),
+        Span::Semantic(SemanticSpan::Delete(vec![Span::Inline("bar".into())])),
+        Span::Inline(" ".into()),
+        Span::Macro(MacroSpan::Linebreak),
+      ]
+    );
+  }
+
+  #[test]
+  fn escape() {
+    assert_eq!(
+      span_list("\\[[a]] \\'''b'''"),
+      vec![
+        Span::Escaped('['),
+        Span::Inline("[a]] ".into()),
+        Span::Escaped('\''),
+        Span::Inline("''b'''".into()),
+      ]
+    );
+    assert_eq!(
+      span_list("\\\\''a'' \\나 \\"),
+      vec![
+        Span::Escaped('\\'),
+        Span::Semantic(SemanticSpan::Emphasis(vec![Span::Inline("a".into())])),
+        Span::Inline(" ".into()),
+        Span::Escaped('나'),
+        Span::Inline(" \\".into()),
+      ]
+    );
+  }
+
+  #[test]
+  fn escape_bracket() {
+    assert_eq!(
+      span_list("\\{{{a}}} {{{\\}}}"),
+      vec![
+        Span::Escaped('{'),
+        Span::Inline("{{a}}} ".into()),
+        Span::Bracket(BracketSpan::Inline("\\".into())),
+      ]
+    );
+  }
+
+  #[test]
+  fn escape_does_not_cross_line() {
+    assert_eq!(
+      span_list("a\\\nb"),
+      vec![
+        Span::Inline("a\\".into()),
+        Span::Semantic(SemanticSpan::Linebreak),
+        Span::Inline("b".into()),
+      ]
+    );
+  }
+
+  #[test]
+  fn escape_block() {
+    assert_eq!(
+      parse("\\== a ==\n\\>b\n\\----\n\\{{{c\nd}}}"),
+      vec![Block::Multiline(MultilineBlock::Paragraph(vec![
+        Span::Escaped('='),
+        Span::Inline("= a ==".into()),
+        Span::Semantic(SemanticSpan::Linebreak),
+        Span::Escaped('>'),
+        Span::Inline("b".into()),
+        Span::Semantic(SemanticSpan::Linebreak),
+        Span::Escaped('-'),
+        Span::Inline("---".into()),
+        Span::Semantic(SemanticSpan::Linebreak),
+        Span::Escaped('{'),
+        Span::Inline("{{c".into()),
+        Span::Semantic(SemanticSpan::Linebreak),
+        Span::Inline("d}}}".into()),
+      ]))]
+    );
+  }
+
+  #[test]
+  fn disable_escape() {
+    let options = ParseOptions::new().disable(Syntax::Escape);
+    assert_eq!(
+      parse_with("\\''a''", &options),
+      vec![Block::Multiline(MultilineBlock::Paragraph(vec![
+        Span::Inline("\\".into()),
+        Span::Semantic(SemanticSpan::Emphasis(vec![Span::Inline("a".into())])),
+      ]))]
+    );
   }
 }
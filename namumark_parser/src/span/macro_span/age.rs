@@ -1,14 +1,14 @@
+use super::arguments::arguments;
 use crate::{MacroSpan, Result, EMPTY};
-use nom::{
-  bytes::complete::{tag, take_till},
-  character::complete::char,
-  combinator::all_consuming,
-};
+use nom::{bytes::complete::tag, error::ErrorKind, Err};
 
 pub(crate) fn age(input: &str) -> Result<MacroSpan> {
   let (input, _) = identifier(input)?;
-  let (input, _) = parens(input)?;
-  let span = MacroSpan::Age(input);
+  let (_, arguments) = arguments(input)?;
+  let value = arguments
+    .into_positional(0)
+    .ok_or(Err::Error((input, ErrorKind::Verify)))?;
+  let span = MacroSpan::Age(value);
 
   Ok((EMPTY, span))
 }
@@ -19,14 +19,6 @@ fn identifier(input: &str) -> Result {
   Ok((input, ()))
 }
 
-fn parens(input: &str) -> Result {
-  let (input, _) = char('(')(input)?;
-  let (end_input, input) = take_till(|character| character == ')')(input)?;
-  let _ = all_consuming(char(')'))(end_input)?;
-
-  Ok((input, ()))
-}
-
 #[cfg(test)]
 mod tests {
   use crate::*;
@@ -36,7 +28,7 @@ mod tests {
     let source = "[age(2020-01-01)]";
     assert_eq!(
       span_list(source),
-      vec![Span::Macro(MacroSpan::Age("2020-01-01"))]
+      vec![Span::Macro(MacroSpan::Age("2020-01-01".into()))]
     );
   }
 }
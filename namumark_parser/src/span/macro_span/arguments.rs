@@ -0,0 +1,193 @@
+use crate::{Result, EMPTY};
+use nom::{character::complete::char, error::ErrorKind, Err};
+use std::borrow::Cow;
+
+const ESCAPE: char = '\\';
+const QUOTE: char = '"';
+
+type NamedArgument<'a> = (Cow<'a, str>, Cow<'a, str>);
+
+/// `(a, b, key=value)` 형식의 매크로 인자.
+/// `\`로 다음 글자를 이스케이프하고, 따옴표나 괄호 안의 `,`, `=`는 구분자로 취급하지 않는다
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct MacroArguments<'a> {
+  positional_list: Vec<Cow<'a, str>>,
+  named_list: Vec<NamedArgument<'a>>,
+}
+
+impl<'a> MacroArguments<'a> {
+  pub fn parse(input: &'a str) -> Self {
+    let mut arguments: MacroArguments = Default::default();
+    for token in split(input, ',') {
+      match find(token, '=') {
+        Some(index) => {
+          let key = token[..index].trim();
+          let value = unescape(&token[index + 1..]);
+          arguments.named_list.push((key.into(), value));
+        }
+        None => arguments.positional_list.push(unescape(token)),
+      }
+    }
+
+    arguments
+  }
+
+  pub fn positional(&self, index: usize) -> Option<&str> {
+    self.positional_list.get(index).map(Cow::as_ref)
+  }
+
+  /// 같은 이름이 여러 번 나오면 마지막 값을 사용한다
+  pub fn named(&self, key: &str) -> Option<&str> {
+    self
+      .named_list
+      .iter()
+      .rev()
+      .find(|(name, _)| *name == key)
+      .map(|(_, value)| value.as_ref())
+  }
+
+  pub fn positional_iter(&self) -> impl Iterator<Item = &str> {
+    self.positional_list.iter().map(Cow::as_ref)
+  }
+
+  pub fn named_iter(&self) -> impl Iterator<Item = (&str, &str)> {
+    self
+      .named_list
+      .iter()
+      .map(|(key, value)| (key.as_ref(), value.as_ref()))
+  }
+
+  pub(crate) fn into_positional(self, index: usize) -> Option<Cow<'a, str>> {
+    self.positional_list.into_iter().nth(index)
+  }
+
+  pub(crate) fn from_parts(
+    positional_list: Vec<Cow<'a, str>>,
+    named_list: Vec<NamedArgument<'a>>,
+  ) -> Self {
+    MacroArguments {
+      positional_list,
+      named_list,
+    }
+  }
+
+  pub(crate) fn into_parts(self) -> (Vec<Cow<'a, str>>, Vec<NamedArgument<'a>>) {
+    (self.positional_list, self.named_list)
+  }
+}
+
+/// 매크로 이름 뒤의 `(...)`. 괄호는 입력의 끝까지 이어져야 한다
+pub(crate) fn arguments<'a>(input: &'a str) -> Result<'a, MacroArguments<'a>> {
+  let (input, _) = char('(')(input)?;
+  if !input.ends_with(')') {
+    return Err(Err::Error((input, ErrorKind::Char)));
+  }
+  let arguments = MacroArguments::parse(&input[..input.len() - 1]);
+
+  Ok((EMPTY, arguments))
+}
+
+/// 이스케이프, 따옴표, 괄호 밖에 있는 구분자 위치마다 나눈다
+fn split(input: &str, separator: char) -> Vec<&str> {
+  let mut token_list = vec![];
+  let mut rest = input;
+  while let Some(index) = find(rest, separator) {
+    token_list.push(&rest[..index]);
+    rest = &rest[index + separator.len_utf8()..];
+  }
+  token_list.push(rest);
+
+  token_list
+}
+
+fn find(input: &str, separator: char) -> Option<usize> {
+  let mut is_escaped = false;
+  let mut is_quoted = false;
+  let mut depth = 0usize;
+  for (index, character) in input.char_indices() {
+    match character {
+      _ if is_escaped => is_escaped = false,
+      ESCAPE => is_escaped = true,
+      QUOTE => is_quoted = !is_quoted,
+      _ if is_quoted => {}
+      '(' => depth += 1,
+      ')' => depth = depth.saturating_sub(1),
+      _ if character == separator && depth == 0 => return Some(index),
+      _ => {}
+    }
+  }
+
+  None
+}
+
+/// 앞뒤 공백과 감싸는 따옴표를 벗겨내고 이스케이프를 푼다
+fn unescape<'a>(input: &'a str) -> Cow<'a, str> {
+  let input = input.trim();
+  let input = if input.len() >= 2 && input.starts_with(QUOTE) && input.ends_with(QUOTE) {
+    &input[1..input.len() - 1]
+  } else {
+    input
+  };
+  if !input.contains(ESCAPE) {
+    return Cow::Borrowed(input);
+  }
+
+  let mut value = String::with_capacity(input.len());
+  let mut character_list = input.chars();
+  while let Some(character) = character_list.next() {
+    match character {
+      ESCAPE => value.push(character_list.next().unwrap_or(ESCAPE)),
+      _ => value.push(character),
+    }
+  }
+
+  Cow::Owned(value)
+}
+
+#[cfg(test)]
+mod tests {
+  use crate::*;
+
+  #[test]
+  fn positional_and_named() {
+    let arguments = MacroArguments::parse("foo, bar , key = value");
+    assert_eq!(
+      arguments.positional_iter().collect::<Vec<_>>(),
+      vec!["foo", "bar"]
+    );
+    assert_eq!(arguments.named("key"), Some("value"));
+    assert_eq!(arguments.named("foo"), None);
+  }
+
+  #[test]
+  fn escape() {
+    let arguments = MacroArguments::parse(r"a\,b, c\=d, e\\");
+    assert_eq!(
+      arguments.positional_iter().collect::<Vec<_>>(),
+      vec!["a,b", "c=d", r"e\"]
+    );
+  }
+
+  #[test]
+  fn quote() {
+    let arguments = MacroArguments::parse(r#""a, b", text=" c=d ", "e\"f""#);
+    assert_eq!(
+      arguments.positional_iter().collect::<Vec<_>>(),
+      vec!["a, b", "e\"f"]
+    );
+    assert_eq!(arguments.named("text"), Some(" c=d "));
+  }
+
+  #[test]
+  fn parens() {
+    let arguments = MacroArguments::parse("f(a, b), text=a(b=c)");
+    assert_eq!(arguments.positional(0), Some("f(a, b)"));
+    assert_eq!(arguments.named("text"), Some("a(b=c)"));
+  }
+
+  #[test]
+  fn empty() {
+    let arguments = MacroArguments::parse("");
+    assert_eq!(arguments.positional_iter().collect::<Vec<_>>(), vec![""]);
+  }
+}
@@ -1,14 +1,11 @@
-use crate::{span_list, whitespace, MacroSpan, Result, EMPTY};
+use crate::{span_list_with, whitespace, Context, MacroSpan, Result, EMPTY};
 use nom::{bytes::complete::take_until, character::complete::char, combinator::opt};
 
-pub(crate) fn comment(input: &str) -> Result<MacroSpan> {
+pub(crate) fn comment<'a>(input: &'a str, context: Context) -> Result<'a, MacroSpan<'a>> {
   let (input, _) = identifier(input)?;
   let (input, description) = opt(description)(input)?;
-  let span_list = span_list(whitespace(input));
-  let span = MacroSpan::Comment(
-    span_list,
-    description.map(|description| description).unwrap_or(""),
-  );
+  let span_list = span_list_with(whitespace(input), context);
+  let span = MacroSpan::Comment(span_list, description.unwrap_or("").into());
 
   Ok((EMPTY, span))
 }
@@ -35,8 +32,8 @@ mod tests {
     assert_eq!(
       span_list(source),
       vec![Span::Macro(MacroSpan::Comment(
-        vec![Span::Inline("Danuel")],
-        ""
+        vec![Span::Inline("Danuel".into())],
+        "".into()
       ))]
     );
   }
@@ -47,9 +44,28 @@ mod tests {
     assert_eq!(
       span_list(source),
       vec![Span::Macro(MacroSpan::Comment(
-        vec![Span::Inline("Danuel")],
-        "Foo"
+        vec![Span::Inline("Danuel".into())],
+        "Foo".into()
       ))]
     );
   }
+
+  #[test]
+  fn escaped_bracket() {
+    let source = "[* a\\]b]c";
+    assert_eq!(
+      span_list(source),
+      vec![
+        Span::Macro(MacroSpan::Comment(
+          vec![
+            Span::Inline("a".into()),
+            Span::Escaped(']'),
+            Span::Inline("b".into())
+          ],
+          "".into()
+        )),
+        Span::Inline("c".into())
+      ]
+    );
+  }
 }
@@ -0,0 +1,45 @@
+use super::arguments::arguments;
+use crate::{Context, MacroSpan, Result, EMPTY};
+use nom::{bytes::complete::take_till, error::ErrorKind, Err};
+
+pub(crate) fn custom<'a>(input: &'a str, context: Context) -> Result<'a, MacroSpan<'a>> {
+  let (rest, name) = take_till(|character| character == '(' || character == '[')(input)?;
+  let handler = context
+    .options
+    .macro_handler_of(name)
+    .ok_or(Err::Error((input, ErrorKind::Verify)))?;
+  let arguments = match rest.chars().next() {
+    Some('(') => Some(arguments(rest)?.1),
+    Some(_) => return Err(Err::Error((input, ErrorKind::Verify))),
+    None => None,
+  };
+  if handler.accept(arguments.as_ref()) {
+    let span = MacroSpan::Custom {
+      name: name.into(),
+      arguments,
+    };
+
+    Ok((EMPTY, span))
+  } else {
+    Err(Err::Error((input, ErrorKind::Verify)))
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use crate::*;
+
+  #[test]
+  fn nested_parens() {
+    let options = ParseOptions::new().custom_macro("vote");
+    assert_eq!(
+      parse_with("[vote(a (b))]", &options),
+      vec![Block::Multiline(MultilineBlock::Paragraph(vec![
+        Span::Macro(MacroSpan::Custom {
+          name: "vote".into(),
+          arguments: Some(MacroArguments::parse("a (b)"))
+        })
+      ]))]
+    );
+  }
+}
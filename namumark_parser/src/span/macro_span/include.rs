@@ -1,16 +1,17 @@
-use crate::{MacroSpan, Result, EMPTY};
-use nom::{
-  bytes::complete::{tag, take_till},
-  character::complete::char,
-  combinator::{all_consuming, opt},
-};
-
-// TODO(Danuel): value 문법 확인 후 구현 추가 필요
-pub(crate) fn include(input: &str) -> Result<MacroSpan> {
+use super::arguments::arguments;
+use crate::{Context, MacroSpan, Result, EMPTY};
+use nom::{bytes::complete::tag, error::ErrorKind, Err};
+
+/// `[include(틀:foo, key=value)]`. 이름이 붙은 인자는 틀에 넘겨줄 값이다
+pub(crate) fn include<'a>(input: &'a str, context: Context) -> Result<'a, MacroSpan<'a>> {
   let (input, _) = identifier(input)?;
-  let (input, _) = parens(input)?;
-  let (_, namespace) = namespace(input)?;
-  let span = MacroSpan::Include(namespace);
+  let (_, arguments) = arguments(input)?;
+  let (positional_list, parameter_list) = arguments.into_parts();
+  let title = positional_list
+    .into_iter()
+    .next()
+    .ok_or(Err::Error((input, ErrorKind::Verify)))?;
+  let span = MacroSpan::Include(context.namespaces().title(title), parameter_list);
 
   Ok((EMPTY, span))
 }
@@ -21,21 +22,6 @@ fn identifier(input: &str) -> Result {
   Ok((input, ()))
 }
 
-fn parens(input: &str) -> Result {
-  let (input, _) = char('(')(input)?;
-  let (end_input, input) = take_till(|character| character == ')')(input)?;
-  let _ = all_consuming(char(')'))(end_input)?;
-
-  Ok((input, ()))
-}
-
-fn namespace(input: &str) -> Result<&str> {
-  let (input, namespace) = take_till(|character| character == ',')(input)?;
-  let (input, _) = opt(char(','))(input)?;
-
-  Ok((input, namespace))
-}
-
 #[cfg(test)]
 mod tests {
   use crate::*;
@@ -43,15 +29,97 @@ mod tests {
   #[test]
   fn empty() {
     let source = "[include()]";
-    assert_eq!(span_list(source), vec![Span::Macro(MacroSpan::Include(""))]);
+    assert_eq!(
+      span_list(source),
+      vec![Span::Macro(MacroSpan::Include(
+        PageTitle::new(None, ""),
+        vec![]
+      ))]
+    );
   }
 
   #[test]
-  fn namespace() {
+  fn title() {
     let source = "[include(foo)]";
     assert_eq!(
       span_list(source),
-      vec![Span::Macro(MacroSpan::Include("foo"))]
+      vec![Span::Macro(MacroSpan::Include(
+        PageTitle::new(None, "foo"),
+        vec![]
+      ))]
+    );
+  }
+
+  #[test]
+  fn namespace() {
+    let source = "[include(틀:foo)]";
+    assert_eq!(
+      span_list(source),
+      vec![Span::Macro(MacroSpan::Include(
+        PageTitle::new(Some("틀"), "foo"),
+        vec![]
+      ))]
+    );
+  }
+
+  #[test]
+  fn alias() {
+    let source = "[include(Template:foo)]";
+    assert_eq!(
+      span_list(source),
+      vec![Span::Macro(MacroSpan::Include(
+        PageTitle::new(Some("틀"), "foo"),
+        vec![]
+      ))]
+    );
+  }
+
+  #[test]
+  fn parameter() {
+    let source = "[include(틀:x, text=a(b), name = \\,)]";
+    assert_eq!(
+      span_list(source),
+      vec![Span::Macro(MacroSpan::Include(
+        PageTitle::new(Some("틀"), "x"),
+        vec![("text".into(), "a(b)".into()), ("name".into(), ",".into())]
+      ))]
+    );
+  }
+
+  #[test]
+  fn escaped_title() {
+    let source = r"[include(틀:a\,b)]";
+    assert_eq!(
+      span_list(source),
+      vec![Span::Macro(MacroSpan::Include(
+        PageTitle::new(Some("틀"), "a,b"),
+        vec![]
+      ))]
+    );
+  }
+
+  #[test]
+  fn bracket_in_argument() {
+    assert_eq!(
+      span_list("[include(틀:x, text=[[a]])] b"),
+      vec![
+        Span::Macro(MacroSpan::Include(
+          PageTitle::new(Some("틀"), "x"),
+          vec![("text".into(), "[[a]]".into())]
+        )),
+        Span::Inline(" b".into())
+      ]
+    );
+    assert_eq!(
+      span_list("[include(x, a=])] [include(y)]"),
+      vec![
+        Span::Macro(MacroSpan::Include(
+          PageTitle::new(None, "x"),
+          vec![("a".into(), "]".into())]
+        )),
+        Span::Inline(" ".into()),
+        Span::Macro(MacroSpan::Include(PageTitle::new(None, "y"), vec![]))
+      ]
     );
   }
 }
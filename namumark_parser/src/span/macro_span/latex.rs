@@ -1,14 +1,10 @@
 use crate::{MacroSpan, Result, EMPTY};
-use nom::{
-  bytes::complete::{tag, take_till},
-  character::complete::char,
-  combinator::all_consuming,
-};
+use nom::{bytes::complete::tag, character::complete::char, error::ErrorKind, Err};
 
 pub(crate) fn latex(input: &str) -> Result<MacroSpan> {
   let (input, _) = identifier(input)?;
   let (input, _) = parens(input)?;
-  let span = MacroSpan::Latex(input);
+  let span = MacroSpan::Latex(input.into());
 
   Ok((EMPTY, span))
 }
@@ -19,12 +15,14 @@ fn identifier(input: &str) -> Result {
   Ok((input, ()))
 }
 
+/// 수식은 `\`를 그대로 사용하므로 인자 문법을 적용하지 않고 괄호 안을 통째로 가져온다
 fn parens(input: &str) -> Result {
   let (input, _) = char('(')(input)?;
-  let (end_input, input) = take_till(|character| character == ')')(input)?;
-  let _ = all_consuming(char(')'))(end_input)?;
+  if !input.ends_with(')') {
+    return Err(Err::Error((input, ErrorKind::Char)));
+  }
 
-  Ok((input, ()))
+  Ok((&input[..input.len() - 1], ()))
 }
 
 #[cfg(test)]
@@ -36,7 +34,16 @@ mod tests {
     let source = "[math(Danuel)]";
     assert_eq!(
       span_list(source),
-      vec![Span::Macro(MacroSpan::Latex("Danuel"))]
+      vec![Span::Macro(MacroSpan::Latex("Danuel".into()))]
+    );
+  }
+
+  #[test]
+  fn parens() {
+    let source = r"[math(\left( x \right))]";
+    assert_eq!(
+      span_list(source),
+      vec![Span::Macro(MacroSpan::Latex(r"\left( x \right)".into()))]
     );
   }
 }
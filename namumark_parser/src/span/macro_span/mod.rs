@@ -1,6 +1,8 @@
 mod age;
 mod anchor;
+mod arguments;
 mod comment;
+mod custom;
 mod date;
 mod datetime;
 mod dday;
@@ -12,10 +14,13 @@ mod page_count;
 mod ruby;
 mod table_of_contents;
 
-use crate::{line, Color, Result, Span};
+use crate::{Color, Context, PageTitle, Result, Span, Syntax};
 use age::age;
 use anchor::anchor;
+pub(crate) use arguments::arguments;
+pub use arguments::MacroArguments;
 use comment::comment;
+use custom::custom;
 use date::date;
 use datetime::datetime;
 use dday::dday;
@@ -23,65 +28,58 @@ use footnote::footnote;
 use include::include;
 use latex::latex;
 use linebreak::linebreak;
-use nom::{branch::alt, bytes::complete::take_till, character::complete::char};
+use nom::branch::alt;
 use page_count::page_count;
 use ruby::ruby;
+use std::borrow::Cow;
 use table_of_contents::table_of_contents;
 
 #[derive(Debug, PartialEq)]
 pub enum MacroSpan<'a> {
-  Age(&'a str),
-  Anchor(&'a str),
-  Comment(Vec<Span<'a>>, &'a str),
+  Age(Cow<'a, str>),
+  Anchor(Cow<'a, str>),
+  Comment(Vec<Span<'a>>, Cow<'a, str>),
+  /// `ParseOptions`에 등록된 사용자 정의 매크로
+  Custom {
+    name: Cow<'a, str>,
+    arguments: Option<MacroArguments<'a>>,
+  },
   Date,
   Datetime,
-  Dday(&'a str),
+  Dday(Cow<'a, str>),
   Footnote,
-  Include(&'a str),
-  Latex(&'a str),
+  /// 문서 제목과 틀에 넘겨줄 `key=value` 인자
+  Include(PageTitle<'a>, Vec<(Cow<'a, str>, Cow<'a, str>)>),
+  Latex(Cow<'a, str>),
   Linebreak,
-  PageCount(Option<&'a str>),
-  Ruby(Option<(&'a str, RubyOption<'a>)>),
+  PageCount(Option<Cow<'a, str>>),
+  Ruby(Option<(Cow<'a, str>, RubyOption<'a>)>),
   TableOfContents,
 }
 
 #[derive(Debug, Default, PartialEq)]
 pub struct RubyOption<'a> {
-  color: Color<'a>,
-  text: &'a str,
+  pub color: Color<'a>,
+  pub text: Cow<'a, str>,
 }
 
-pub(crate) fn macro_span(input: &str) -> Result<MacroSpan> {
-  fn parens(input: &str) -> Result<&str> {
-    let (input, _) = char('[')(input)?;
-    let (input, line) = take_till(|character| character == ']')(input)?;
-    let (input, _) = char(']')(input)?;
-
-    Ok((input, line))
-  };
-
-  let (input, line) = parens(input)?;
-  let (_, span) = alt((
+/// `[`와 `]` 사이의 내용. 괄호는 스캐너가 찾아서 벗겨낸다
+pub(crate) fn macro_body<'a>(input: &'a str, context: Context) -> Result<'a, MacroSpan<'a>> {
+  let (input, _) = context.expect(input, Syntax::Macro)?;
+  alt((
+    |input| custom(input, context),
     footnote,
     linebreak,
     date,
     datetime,
     page_count,
-    include,
+    |input| include(input, context),
     table_of_contents,
     anchor,
     latex,
     age,
     dday,
     ruby,
-    comment,
-  ))(line)?;
-
-  Ok((input, span))
-}
-
-pub(crate) fn starts_with_macro_span(input: &str) -> bool {
-  let (_, input) = line(input);
-
-  input.starts_with('[') && input.find(']').is_some() && macro_span(input).is_ok()
+    |input| comment(input, context),
+  ))(input)
 }
@@ -1,14 +1,12 @@
+use super::arguments::arguments;
 use crate::{MacroSpan, Result, EMPTY};
-use nom::{
-  bytes::complete::{tag, take_until},
-  character::complete::char,
-  combinator::{all_consuming, opt},
-};
+use nom::{bytes::complete::tag, combinator::opt};
 
 pub(crate) fn page_count(input: &str) -> Result<MacroSpan> {
   let (input, _) = identifier(input)?;
-  let (_, namespace) = opt(parens)(input)?;
-  let span = MacroSpan::PageCount(namespace.map(|namespace| namespace));
+  let (_, arguments) = opt(arguments)(input)?;
+  let namespace = arguments.and_then(|arguments| arguments.into_positional(0));
+  let span = MacroSpan::PageCount(namespace);
 
   Ok((EMPTY, span))
 }
@@ -19,14 +17,6 @@ fn identifier(input: &str) -> Result {
   Ok((input, ()))
 }
 
-fn parens(input: &str) -> Result<&str> {
-  let (input, _) = char('(')(input)?;
-  let (end_input, input) = take_until(")")(input)?;
-  let _ = all_consuming(char(')'))(end_input)?;
-
-  Ok((EMPTY, input))
-}
-
 #[cfg(test)]
 mod tests {
   use crate::*;
@@ -45,7 +35,7 @@ mod tests {
     let source = "[pagecount(file)]";
     assert_eq!(
       span_list(source),
-      vec![Span::Macro(MacroSpan::PageCount(Some("file")))]
+      vec![Span::Macro(MacroSpan::PageCount(Some("file".into())))]
     );
   }
 }
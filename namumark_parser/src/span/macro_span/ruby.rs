@@ -1,23 +1,21 @@
+use super::arguments::arguments;
 use crate::{MacroSpan, Result, RubyOption, EMPTY};
-use nom::{
-  branch::alt,
-  bytes::complete::{tag, take_till},
-  character::complete::char,
-  combinator::{all_consuming, opt},
-};
+use nom::bytes::complete::tag;
 
 pub(crate) fn ruby(input: &str) -> Result<MacroSpan> {
   let (input, _) = identifier(input)?;
-  let (input, _) = parens(input)?;
-  if let Ok((input, word)) = word(input) {
-    if let Ok((_, ruby_option)) = ruby_option(input) {
-      let span = MacroSpan::Ruby(Some((word, ruby_option)));
-
-      return Ok((EMPTY, span));
+  let (_, arguments) = arguments(input)?;
+  let (positional_list, named_list) = arguments.into_parts();
+  let mut ruby_option: RubyOption = Default::default();
+  for (key, value) in named_list {
+    match key.as_ref() {
+      "ruby" => ruby_option.text = value,
+      "color" => ruby_option.color = value.into(),
+      _ => {}
     }
   }
-
-  let span = MacroSpan::Ruby(None);
+  let word = positional_list.into_iter().next();
+  let span = MacroSpan::Ruby(word.map(|word| (word, ruby_option)));
 
   Ok((EMPTY, span))
 }
@@ -28,39 +26,6 @@ fn identifier(input: &str) -> Result {
   Ok((input, ()))
 }
 
-fn parens(input: &str) -> Result {
-  let (input, _) = char('(')(input)?;
-  let (end_input, input) = take_till(|character| character == ')')(input)?;
-  let _ = all_consuming(char(')'))(end_input)?;
-
-  Ok((input, ()))
-}
-
-fn word(input: &str) -> Result<&str> {
-  let (input, word) = take_till(|character| character == ',' || character == ')')(input)?;
-  let (input, _) = opt(alt((char(','), char(')'))))(input)?;
-
-  Ok((input, word))
-}
-
-fn ruby_option(input: &str) -> Result<RubyOption> {
-  let mut ruby_option: RubyOption = Default::default();
-  for token in input.split(',') {
-    let token_list: Vec<&str> = token.split('=').map(|token| token.trim()).collect();
-    match *token_list.as_slice() {
-      ["ruby", value] => {
-        ruby_option.text = value;
-      }
-      ["color", value] => {
-        ruby_option.color = value.into();
-      }
-      _ => {}
-    }
-  }
-
-  Ok((EMPTY, ruby_option))
-}
-
 #[cfg(test)]
 mod tests {
   use crate::*;
@@ -71,9 +36,9 @@ mod tests {
     assert_eq!(
       span_list(source),
       vec![Span::Macro(MacroSpan::Ruby(Some((
-        "foo",
+        "foo".into(),
         RubyOption {
-          text: "bar",
+          text: "bar".into(),
           ..Default::default()
         }
       ))))]
@@ -86,12 +51,27 @@ mod tests {
     assert_eq!(
       span_list(source),
       vec![Span::Macro(MacroSpan::Ruby(Some((
-        "foo",
+        "foo".into(),
         RubyOption {
-          text: "bar",
+          text: "bar".into(),
           color: Color::Hex(0, 0, 0)
         }
       ))))]
     );
   }
+
+  #[test]
+  fn escaped_comma() {
+    let source = r"[ruby(a\,b, ruby=c)]";
+    assert_eq!(
+      span_list(source),
+      vec![Span::Macro(MacroSpan::Ruby(Some((
+        "a,b".into(),
+        RubyOption {
+          text: "c".into(),
+          ..Default::default()
+        }
+      ))))]
+    );
+  }
 }
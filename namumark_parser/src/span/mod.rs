@@ -5,22 +5,25 @@ mod macro_span;
 mod semantic_span;
 
 use super::constants::EMPTY;
-use crate::{should_be_empty, Result};
-use bracket_span::bracket_span;
+use crate::{Context, ParseOptions, Result};
+pub(crate) use bracket_span::bracket_pair_list;
 pub use bracket_span::BracketSpan;
-use command_span::command_span;
-pub use command_span::{CommandSpan, ImageOption, VideoOption, VideoPlatform};
-use inline::inline;
-use macro_span::macro_span;
-pub use macro_span::{MacroSpan, RubyOption};
+pub use command_span::{
+  Category, CommandSpan, ImageOption, ImageRendering, ObjectFit, Theme, VideoOption, VideoPlatform,
+};
+pub use macro_span::{MacroArguments, MacroSpan, RubyOption};
 use nom::{
-  bytes::complete::{tag, take},
+  bytes::complete::{is_a, tag},
   character::complete::char,
   combinator::all_consuming,
+  error::ErrorKind,
   number::complete::float,
+  Err,
 };
-use semantic_span::semantic_span;
 pub use semantic_span::SemanticSpan;
+use std::borrow::Cow;
+
+const HEX_DIGIT: &str = "0123456789abcdefABCDEF";
 
 #[derive(Debug, PartialEq)]
 pub enum Span<'a> {
@@ -28,7 +31,9 @@ pub enum Span<'a> {
   Bracket(BracketSpan<'a>),
   Macro(MacroSpan<'a>),
   Command(CommandSpan<'a>),
-  Inline(&'a str),
+  Inline(Cow<'a, str>),
+  /// `\`로 이스케이프된 글자. 문법으로 해석하지 않는다
+  Escaped(char),
 }
 
 #[allow(dead_code)]
@@ -52,7 +57,7 @@ pub enum Alignment {
   Right,
 }
 
-#[derive(Debug, PartialEq)]
+#[derive(Debug, Clone, Copy, PartialEq)]
 pub enum FontSizeLevel {
   One,
   Two,
@@ -61,12 +66,12 @@ pub enum FontSizeLevel {
   Five,
 }
 
-#[derive(Debug, PartialEq)]
+#[derive(Debug, Clone, PartialEq)]
 pub enum Color<'a> {
   Hex(u8, u8, u8),
   Rgb(u8, u8, u8),
   Hsl(u8, u8, u8),
-  Raw(&'a str),
+  Raw(Cow<'a, str>),
 }
 
 impl From<usize> for FontSizeLevel {
@@ -139,28 +144,46 @@ impl<'a> Default for Color<'a> {
   }
 }
 
-impl<'a> From<&str> for Color<'a> {
+impl<'a> From<&'a str> for Color<'a> {
+  fn from(source: &'a str) -> Self {
+    Cow::Borrowed(source).into()
+  }
+}
+
+impl<'a> From<Cow<'a, str>> for Color<'a> {
   // TODO(Danuel): RGB, HSL 문법 추가
-  fn from(source: &str) -> Self {
+  /// `#rgb`, `#rrggbb`가 아닌 값은 `red`, `dark` 같은 색 이름으로 보고 그대로 둔다
+  fn from(source: Cow<'a, str>) -> Self {
     fn hex<'a>(input: &str) -> Result<Color<'a>> {
       let (input, _) = char('#')(input)?;
-      let (input, r) = take(2usize)(input)?;
-      let (input, g) = take(2usize)(input)?;
-      let (input, b) = take(2usize)(input)?;
-      let _ = should_be_empty(input)?;
-
-      let r: u8 = r.parse().unwrap();
-      let g: u8 = g.parse().unwrap();
-      let b: u8 = b.parse().unwrap();
-      let color = Color::Hex(r, g, b);
+      let _ = all_consuming(is_a(HEX_DIGIT))(input)?;
+      let channel = |digit: &str| u8::from_str_radix(digit, 16).unwrap();
+      let color = match input.len() {
+        3 => {
+          let (r, g, b) = (&input[0..1], &input[1..2], &input[2..3]);
+          Color::Hex(channel(r) * 17, channel(g) * 17, channel(b) * 17)
+        }
+        6 => Color::Hex(
+          channel(&input[0..2]),
+          channel(&input[2..4]),
+          channel(&input[4..6]),
+        ),
+        _ => return Err(Err::Error((input, ErrorKind::Verify))),
+      };
 
       Ok((EMPTY, color))
     };
 
-    if let Ok((_, color)) = hex(source) {
+    let source = match source {
+      Cow::Borrowed(source) => Cow::Borrowed(source.trim()),
+      Cow::Owned(source) => Cow::Owned(source.trim().to_owned()),
+    };
+    if let Ok((_, color)) = hex(&source) {
       color
-    } else {
+    } else if source.is_empty() {
       Default::default()
+    } else {
+      Color::Raw(source)
     }
   }
 }
@@ -184,33 +207,14 @@ impl From<&str> for Alignment {
   }
 }
 
-pub fn span_list(mut input: &str) -> Vec<Span> {
-  let mut list = vec![];
-  while !input.is_empty() {
-    match span(input) {
-      Ok((next_input, span)) => {
-        list.push(span);
-        input = next_input;
-      }
-      _ => break,
-    }
-  }
-
-  list
+pub fn span_list(input: &str) -> Vec<Span> {
+  span_list_with(input, Context::new(&ParseOptions::default()))
 }
 
-fn span(input: &str) -> Result<Span> {
-  if let Ok((input, span)) = semantic_span(input) {
-    Ok((input, Span::Semantic(span)))
-  } else if let Ok((input, span)) = bracket_span(input) {
-    Ok((input, Span::Bracket(span)))
-  } else if let Ok((input, span)) = command_span(input) {
-    Ok((input, Span::Command(span)))
-  } else if let Ok((input, span)) = macro_span(input) {
-    Ok((input, Span::Macro(span)))
-  } else {
-    let (input, inline) = inline(input)?;
-
-    Ok((input, Span::Inline(inline)))
+pub(crate) fn span_list_with<'a>(input: &'a str, context: Context) -> Vec<Span<'a>> {
+  match context.nested() {
+    Some(context) => inline::span_list(input, context),
+    None if input.is_empty() => vec![],
+    None => vec![Span::Inline(input.into())],
   }
 }
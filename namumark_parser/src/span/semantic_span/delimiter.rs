@@ -0,0 +1,131 @@
+use crate::{Context, Syntax};
+
+/// 짝이 맞은 여는 표시와 닫는 표시의 바이트 범위
+#[derive(Debug, PartialEq)]
+pub(crate) struct DelimiterPair {
+  pub(crate) syntax: Syntax,
+  pub(crate) open: (usize, usize),
+  pub(crate) close: (usize, usize),
+}
+
+/// 같은 표시 바이트가 이어진 구간 가운데 아직 짝을 찾지 못한 부분
+#[derive(Debug)]
+struct Delimiter {
+  marker: u8,
+  start: usize,
+  end: usize,
+  /// 아직 짝을 찾지 못한 문법. 안쪽에 먼저 쓰일 것이 앞에 온다
+  syntax_list: [Option<Syntax>; 2],
+}
+
+/// 한 줄 안에서 여는 표시를 쌓아 두고, 닫는 표시가 나오면 가장 가까운 같은 표시와 짝을 짓는다.
+/// 짝을 지으면 그 사이에 남아 있던 여는 표시는 일반 텍스트가 되므로 서로 엇갈리는 짝은 생기지 않는다
+#[derive(Debug, Default)]
+pub(crate) struct DelimiterStack {
+  delimiter_list: Vec<Delimiter>,
+  pair_list: Vec<DelimiterPair>,
+}
+
+impl Delimiter {
+  /// `'`는 두 개가 기울임, 세 개가 굵게, 다섯 개가 둘 다이다. 나머지 표시는 정확히 두 개여야 한다
+  fn new(marker: u8, start: usize, end: usize, context: Context) -> Option<Self> {
+    let syntax_list = match (marker, end - start) {
+      (b'\'', 2) => [Some(Syntax::Emphasis), None],
+      (b'\'', 3) | (b'\'', 4) => [Some(Syntax::Strong), None],
+      (b'\'', _) if end - start >= 5 => [Some(Syntax::Emphasis), Some(Syntax::Strong)],
+      (b'~', 2) | (b'-', 2) => [Some(Syntax::Delete), None],
+      (b'_', 2) => [Some(Syntax::Underline), None],
+      (b'^', 2) => [Some(Syntax::Superscript), None],
+      (b',', 2) => [Some(Syntax::Subscript), None],
+      _ => return None,
+    };
+    let syntax_list = syntax_list.map(|syntax| syntax.filter(|syntax| context.is_enabled(*syntax)));
+    if syntax_list.iter().all(Option::is_none) {
+      return None;
+    }
+
+    Some(Delimiter {
+      marker,
+      start,
+      end,
+      syntax_list,
+    })
+  }
+
+  fn has(&self, syntax: Syntax) -> bool {
+    self.syntax_list.contains(&Some(syntax))
+  }
+
+  fn is_empty(&self) -> bool {
+    self.syntax_list.iter().all(Option::is_none)
+  }
+
+  fn remove(&mut self, syntax: Syntax) {
+    for slot in self.syntax_list.iter_mut() {
+      if *slot == Some(syntax) {
+        *slot = None;
+      }
+    }
+  }
+}
+
+impl DelimiterStack {
+  /// `input[start..end]`는 `marker`만으로 이루어져 있어야 한다
+  pub(crate) fn push(&mut self, marker: u8, start: usize, end: usize, context: Context) {
+    let mut run = match Delimiter::new(marker, start, end, context) {
+      Some(run) => run,
+      None => return,
+    };
+
+    while let Some((index, syntax)) = self.opener_of(&run) {
+      let marker_len = if syntax == Syntax::Strong { 3 } else { 2 };
+      // 여는 표시는 오른쪽 끝을, 닫는 표시는 왼쪽 끝을 쓴다
+      let opener = &mut self.delimiter_list[index];
+      self.pair_list.push(DelimiterPair {
+        syntax,
+        open: (opener.end - marker_len, opener.end),
+        close: (run.start, run.start + marker_len),
+      });
+      opener.end -= marker_len;
+      opener.remove(syntax);
+      run.start += marker_len;
+      run.remove(syntax);
+
+      let len = if opener.is_empty() { index } else { index + 1 };
+      self.delimiter_list.truncate(len);
+      if run.is_empty() {
+        return;
+      }
+    }
+    self.delimiter_list.push(run);
+  }
+
+  /// 줄이 끝나면 짝을 찾지 못한 여는 표시를 버린다
+  pub(crate) fn end_line(&mut self) {
+    self.delimiter_list.clear();
+  }
+
+  /// 여는 표시가 앞에 오는 순서로 정렬된 짝
+  pub(crate) fn into_pair_list(mut self) -> Vec<DelimiterPair> {
+    self.pair_list.sort_by_key(|pair| pair.open.0);
+    self.pair_list
+  }
+
+  /// `run`과 짝이 될 가장 가까운 여는 표시와 그 문법
+  fn opener_of(&self, run: &Delimiter) -> Option<(usize, Syntax)> {
+    self
+      .delimiter_list
+      .iter()
+      .enumerate()
+      .rev()
+      .filter(|(_, opener)| opener.marker == run.marker)
+      .find_map(|(index, opener)| {
+        run
+          .syntax_list
+          .iter()
+          .flatten()
+          .find(|syntax| opener.has(**syntax))
+          .map(|syntax| (index, *syntax))
+      })
+  }
+}
@@ -1,9 +1,7 @@
-use crate::{span_list, Result, Span};
-use nom::{
-  branch::alt,
-  bytes::complete::{tag, take_until},
-  character::complete::char,
-};
+mod delimiter;
+
+use crate::{Span, Syntax};
+pub(crate) use delimiter::{DelimiterPair, DelimiterStack};
 
 #[derive(Debug, PartialEq)]
 pub enum SemanticSpan<'a> {
@@ -16,74 +14,17 @@ pub enum SemanticSpan<'a> {
   Linebreak,
 }
 
-pub(crate) fn semantic_span(input: &str) -> Result<SemanticSpan> {
-  alt((
-    strong,
-    emphasis,
-    delete1,
-    delete2,
-    underline,
-    superscript,
-    subscript,
-    linebreak,
-  ))(input)
-}
-
-pub(crate) fn starts_with_sematic_span(input: &str) -> bool {
-  expect_strong(input).is_ok()
-    || expect_emphasis(input).is_ok()
-    || expect_delete1(input).is_ok()
-    || expect_delete2(input).is_ok()
-    || expect_underline(input).is_ok()
-    || expect_superscript(input).is_ok()
-    || expect_subscript(input).is_ok()
-    || starts_with_linebreak(input)
-}
-
-macro_rules! semantic_span {
-  ($name:ident, $expect_with_name:ident, $marker:expr, $variant:ident) => {
-    fn $name(input: &str) -> Result<SemanticSpan> {
-      let (input, span_input) = $expect_with_name(input)?;
-      let span_list = span_list(span_input);
-
-      Ok((input, SemanticSpan::$variant(span_list)))
-    }
-
-    fn $expect_with_name(input: &str) -> Result<&str> {
-      const MARKER: &'static str = $marker;
-      const MARKER_COUNT: usize = MARKER.len();
-
-      fn parens(input: &str) -> Result<&str> {
-        let (input, _) = tag(MARKER)(input)?;
-        let (input, span_input) = take_until(MARKER)(input)?;
-
-        Ok((&input[MARKER_COUNT..], span_input))
-      };
-
-      let (input, span_input) = parens(input)?;
-
-      Ok((input, span_input))
-    }
-  };
-}
-
-semantic_span!(strong, expect_strong, "'''", Strong);
-semantic_span!(emphasis, expect_emphasis, "''", Emphasis);
-semantic_span!(delete1, expect_delete1, "~~", Delete);
-semantic_span!(delete2, expect_delete2, "--", Delete);
-semantic_span!(underline, expect_underline, "__", Underline);
-semantic_span!(superscript, expect_superscript, "^^", Superscript);
-semantic_span!(subscript, expect_subscript, ",,", Subscript);
-
-fn linebreak(input: &str) -> Result<SemanticSpan> {
-  let (input, _) = char('\n')(input)?;
-  let span = SemanticSpan::Linebreak;
-
-  Ok((input, span))
-}
-
-fn starts_with_linebreak(input: &str) -> bool {
-  input.starts_with('\n')
+/// 표시 사이의 스팬 목록을 `syntax`에 맞는 스팬으로 만든다
+pub(crate) fn semantic_span<'a>(syntax: Syntax, span_list: Vec<Span<'a>>) -> SemanticSpan<'a> {
+  match syntax {
+    Syntax::Strong => SemanticSpan::Strong(span_list),
+    Syntax::Emphasis => SemanticSpan::Emphasis(span_list),
+    Syntax::Delete => SemanticSpan::Delete(span_list),
+    Syntax::Underline => SemanticSpan::Underline(span_list),
+    Syntax::Superscript => SemanticSpan::Superscript(span_list),
+    Syntax::Subscript => SemanticSpan::Subscript(span_list),
+    _ => unreachable!("{:?} is not a semantic syntax", syntax),
+  }
 }
 
 #[cfg(test)]
@@ -97,7 +38,7 @@ mod tests {
         assert_eq!(
           span_list($source),
           vec![Span::Semantic(SemanticSpan::$variant(vec![Span::Inline(
-            "Danuel"
+            "Danuel".into()
           )]))]
         )
       }
@@ -111,4 +52,100 @@ mod tests {
   semantic_test!(",,Danuel,,", Subscript, subscript);
   semantic_test!("^^Danuel^^", Superscript, superscript);
   semantic_test!("__Danuel__", Underline, underline);
+
+  /// 스팬 목록을 짧은 태그로 나타낸다
+  fn render(span_list: &[Span]) -> String {
+    span_list
+      .iter()
+      .map(|span| match span {
+        Span::Inline(text) => text.to_string(),
+        Span::Semantic(SemanticSpan::Strong(span_list)) => format!("<b>{}</b>", render(span_list)),
+        Span::Semantic(SemanticSpan::Emphasis(span_list)) => {
+          format!("<i>{}</i>", render(span_list))
+        }
+        Span::Semantic(SemanticSpan::Delete(span_list)) => format!("<s>{}</s>", render(span_list)),
+        Span::Semantic(SemanticSpan::Underline(span_list)) => {
+          format!("<u>{}</u>", render(span_list))
+        }
+        Span::Semantic(SemanticSpan::Superscript(span_list)) => {
+          format!("<sup>{}</sup>", render(span_list))
+        }
+        Span::Semantic(SemanticSpan::Subscript(span_list)) => {
+          format!("<sub>{}</sub>", render(span_list))
+        }
+        Span::Semantic(SemanticSpan::Linebreak) => "\n".to_owned(),
+        Span::Command(CommandSpan::Link(span_list, target, _)) => {
+          format!("<a {}>{}</a>", target, render(span_list))
+        }
+        Span::Bracket(BracketSpan::Inline(code)) => format!("<code>{}</code>", code),
+        span => panic!("unexpected {:?}", span),
+      })
+      .collect()
+  }
+
+  #[test]
+  fn delimiter_run() {
+    let table = [
+      // 굵게 안의 짝이 없는 기울임
+      ("'''a''b'''", "<b>a''b</b>"),
+      ("''a'''b'''c''", "<i>a<b>b</b>c</i>"),
+      // 다섯 개는 굵게와 기울임이고, 먼저 닫히는 쪽이 안쪽이다
+      ("'''''x'''''", "<b><i>x</i></b>"),
+      ("'''''x'''y''", "<i><b>x</b>y</i>"),
+      ("'''''x''y'''", "<b><i>x</i>y</b>"),
+      ("''a'''b'''''", "<i>a<b>b</b></i>"),
+      ("'''a'''''b''", "<b>a</b><i>b</i>"),
+      // 굵게와 기울임은 서로 짝이 되지 않고, 짝이 맞지 않아 남는 `'`는 일반 텍스트다
+      ("'''a''", "'''a''"),
+      ("''a'''", "''a'''"),
+      ("''''a''''", "'<b>a</b>'"),
+      ("''a''''b''", "<i>a''''b</i>"),
+      ("'a'", "'a'"),
+      ("''a", "''a"),
+      ("a''", "a''"),
+      ("''''", "''''"),
+      ("'''''", "'''''"),
+      ("''''''", "''''''"),
+      ("''a'' ''b''", "<i>a</i> <i>b</i>"),
+      // 줄을 넘지 않는다
+      ("''a\nb''", "''a\nb''"),
+      ("''a\n''b''", "''a\n<i>b</i>"),
+      ("~~a\nb~~ ~~c~~", "~~a\nb<s> </s>c~~"),
+      // 다른 스팬 안팎의 표시끼리는 짝이 되지 않는다
+      ("''a [[b''c]] d''", "<i>a <a b''c></a> d</i>"),
+      ("[[a|''b]] c''", "<a a>''b</a> c''"),
+      ("''[[a]]''", "<i><a a></a></i>"),
+      ("''{{{a''b}}}''", "<i><code>a''b</code></i>"),
+      ("{{{''a}}}b''", "<code>''a</code>b''"),
+      // 엇갈린 짝은 안쪽의 여는 표시를 버린다
+      ("''a ~~b'' c~~", "<i>a ~~b</i> c~~"),
+      ("~~a--b~~c--", "<s>a--b</s>c--"),
+      ("--a~~b--c~~", "<s>a~~b</s>c~~"),
+      ("__a^^b^^c__", "<u>a<sup>b</sup>c</u>"),
+      (",,a,,b,,c,,", "<sub>a</sub>b<sub>c</sub>"),
+      ("^^a__b^^c__", "<sup>a__b</sup>c__"),
+      // `'`가 아닌 표시는 정확히 두 개여야 한다
+      ("a --- b --- c", "a --- b --- c"),
+      ("~~~a~~", "~~~a~~"),
+      ("~~a~~~", "~~a~~~"),
+      ("--a-- --b--", "<s>a</s> <s>b</s>"),
+      ("____", "____"),
+    ];
+    for (source, expected) in table.iter() {
+      assert_eq!(render(&span_list(source)), *expected, "{:?}", source);
+    }
+  }
+
+  #[test]
+  fn disabled_delimiter() {
+    let options = ParseOptions::new().disable(Syntax::Strong);
+    assert_eq!(
+      parse_with("'''a''' '''''b'''''", &options),
+      vec![Block::Multiline(MultilineBlock::Paragraph(vec![
+        Span::Inline("'''a''' '''".into()),
+        Span::Semantic(SemanticSpan::Emphasis(vec![Span::Inline("b".into())])),
+        Span::Inline("'''".into())
+      ]))]
+    );
+  }
 }
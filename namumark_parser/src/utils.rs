@@ -1,5 +1,7 @@
 use crate::{Result, EMPTY};
-use nom::{character::complete::char, combinator::all_consuming};
+use memchr::memchr;
+use nom::character::complete::char;
+use std::{borrow::Cow, fmt::Write};
 
 pub(crate) fn slice_by(character: char) -> impl Fn(&str) -> &str {
   move |input: &str| -> &str {
@@ -29,12 +31,8 @@ pub(crate) fn whitespace1(input: &str) -> Result {
   Ok((input, ()))
 }
 
-pub(crate) fn should_be_empty(input: &str) -> Result {
-  all_consuming(|input| -> Result { Ok((input, ())) })(input)
-}
-
 pub(crate) fn line(input: &str) -> (&str, &str) {
-  if let Some(index) = input.find('\n') {
+  if let Some(index) = memchr(b'\n', input.as_bytes()) {
     (&input[index + 1..], &input[..index])
   } else {
     (EMPTY, input)
@@ -73,6 +71,10 @@ pub(crate) fn line_with_bracket(input: &str) -> (&str, &str) {
           break;
         }
       }
+      // 괄호 밖의 `\`는 다음 글자를 이스케이프한다
+      _ if index_stack.is_empty() && input.as_bytes()[index] == b'\\' => {
+        index += 2;
+      }
       _ => {
         index += 1;
       }
@@ -85,3 +87,22 @@ pub(crate) fn line_with_bracket(input: &str) -> (&str, &str) {
     _ => ("", input),
   }
 }
+
+/// 주소의 한 부분으로 쓸 수 있도록 영문자, 숫자, `-._~`가 아닌 바이트를 `%XX`로 바꾼다
+pub(crate) fn percent_encode(input: &str) -> Cow<'_, str> {
+  let is_unreserved = |byte: u8| byte.is_ascii_alphanumeric() || b"-._~".contains(&byte);
+  if input.bytes().all(is_unreserved) {
+    return Cow::Borrowed(input);
+  }
+
+  let mut output = String::with_capacity(input.len() * 3);
+  for byte in input.bytes() {
+    if is_unreserved(byte) {
+      output.push(byte as char);
+    } else {
+      write!(output, "%{:02X}", byte).unwrap();
+    }
+  }
+
+  Cow::Owned(output)
+}
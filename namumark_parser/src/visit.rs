@@ -0,0 +1,370 @@
+use crate::{
+  Block, BracketSpan, CommandSpan, ListItem, MacroSpan, MultilineBlock, SemanticSpan,
+  SinglelineBlock, Span,
+};
+
+/// 트리를 앞에서부터 깊이 우선으로 방문한다.
+/// `visit_*`의 기본 구현은 같은 이름의 `walk_*`를 불러 자식 노드로 내려가므로,
+/// 덮어쓴 메서드에서 `walk_*`를 부르지 않으면 그 아래는 방문하지 않는다
+pub trait Visitor<'a> {
+  fn visit_block(&mut self, block: &'a Block<'a>) {
+    self.walk_block(block)
+  }
+
+  fn visit_singleline_block(&mut self, block: &'a SinglelineBlock<'a>) {
+    self.walk_singleline_block(block)
+  }
+
+  fn visit_multiline_block(&mut self, block: &'a MultilineBlock<'a>) {
+    self.walk_multiline_block(block)
+  }
+
+  fn visit_list_item(&mut self, item: &'a ListItem<'a>) {
+    self.walk_list_item(item)
+  }
+
+  fn visit_span(&mut self, span: &'a Span<'a>) {
+    self.walk_span(span)
+  }
+
+  fn visit_semantic_span(&mut self, span: &'a SemanticSpan<'a>) {
+    self.walk_semantic_span(span)
+  }
+
+  fn visit_bracket_span(&mut self, span: &'a BracketSpan<'a>) {
+    self.walk_bracket_span(span)
+  }
+
+  fn visit_macro_span(&mut self, span: &'a MacroSpan<'a>) {
+    self.walk_macro_span(span)
+  }
+
+  fn visit_command_span(&mut self, span: &'a CommandSpan<'a>) {
+    self.walk_command_span(span)
+  }
+
+  fn visit_block_list(&mut self, block_list: &'a [Block<'a>]) {
+    for block in block_list {
+      self.visit_block(block);
+    }
+  }
+
+  fn walk_block(&mut self, block: &'a Block<'a>) {
+    match block {
+      Block::Singleline(block) => self.visit_singleline_block(block),
+      Block::Multiline(block) => self.visit_multiline_block(block),
+    }
+  }
+
+  fn walk_singleline_block(&mut self, block: &'a SinglelineBlock<'a>) {
+    match block {
+      SinglelineBlock::OpenHeading(span_list, _) | SinglelineBlock::ClosedHeading(span_list, _) => {
+        for span in span_list {
+          self.visit_span(span);
+        }
+      }
+      SinglelineBlock::Comment(_) => {}
+    }
+  }
+
+  fn walk_multiline_block(&mut self, block: &'a MultilineBlock<'a>) {
+    match block {
+      MultilineBlock::Blockquote(block_list) | MultilineBlock::Indent(block_list) => {
+        for block in block_list {
+          self.visit_multiline_block(block);
+        }
+      }
+      MultilineBlock::OrderedList(item_list, _, _)
+      | MultilineBlock::UnorderedList(item_list, _) => {
+        for item in item_list {
+          self.visit_list_item(item);
+        }
+      }
+      MultilineBlock::Paragraph(span_list) => {
+        for span in span_list {
+          self.visit_span(span);
+        }
+      }
+      MultilineBlock::HorizontalRule => {}
+    }
+  }
+
+  fn walk_list_item(&mut self, item: &'a ListItem<'a>) {
+    for block in item.iter() {
+      self.visit_multiline_block(block);
+    }
+  }
+
+  fn walk_span(&mut self, span: &'a Span<'a>) {
+    match span {
+      Span::Semantic(span) => self.visit_semantic_span(span),
+      Span::Bracket(span) => self.visit_bracket_span(span),
+      Span::Macro(span) => self.visit_macro_span(span),
+      Span::Command(span) => self.visit_command_span(span),
+      Span::Inline(_) | Span::Escaped(_) => {}
+    }
+  }
+
+  fn walk_semantic_span(&mut self, span: &'a SemanticSpan<'a>) {
+    match span {
+      SemanticSpan::Delete(span_list)
+      | SemanticSpan::Emphasis(span_list)
+      | SemanticSpan::Strong(span_list)
+      | SemanticSpan::Subscript(span_list)
+      | SemanticSpan::Superscript(span_list)
+      | SemanticSpan::Underline(span_list) => {
+        for span in span_list {
+          self.visit_span(span);
+        }
+      }
+      SemanticSpan::Linebreak => {}
+    }
+  }
+
+  fn walk_bracket_span(&mut self, span: &'a BracketSpan<'a>) {
+    match span {
+      BracketSpan::Color(span_list, _)
+      | BracketSpan::SizeDown(span_list, _)
+      | BracketSpan::SizeUp(span_list, _) => {
+        for span in span_list {
+          self.visit_span(span);
+        }
+      }
+      BracketSpan::Folding(block_list) => {
+        for block in block_list {
+          self.visit_multiline_block(block);
+        }
+      }
+      BracketSpan::Inline(_) | BracketSpan::SyntaxHighlight(_, _) => {}
+    }
+  }
+
+  fn walk_macro_span(&mut self, span: &'a MacroSpan<'a>) {
+    if let MacroSpan::Comment(span_list, _) = span {
+      for span in span_list {
+        self.visit_span(span);
+      }
+    }
+  }
+
+  fn walk_command_span(&mut self, span: &'a CommandSpan<'a>) {
+    if let CommandSpan::Link(span_list, _, _) = span {
+      for span in span_list {
+        self.visit_span(span);
+      }
+    }
+  }
+}
+
+/// 트리를 고치면서 방문한다. 방문 순서와 `walk_*`의 역할은 `Visitor`와 같다
+pub trait VisitorMut<'a> {
+  fn visit_block_mut(&mut self, block: &mut Block<'a>) {
+    self.walk_block_mut(block)
+  }
+
+  fn visit_singleline_block_mut(&mut self, block: &mut SinglelineBlock<'a>) {
+    self.walk_singleline_block_mut(block)
+  }
+
+  fn visit_multiline_block_mut(&mut self, block: &mut MultilineBlock<'a>) {
+    self.walk_multiline_block_mut(block)
+  }
+
+  fn visit_list_item_mut(&mut self, item: &mut ListItem<'a>) {
+    self.walk_list_item_mut(item)
+  }
+
+  fn visit_span_mut(&mut self, span: &mut Span<'a>) {
+    self.walk_span_mut(span)
+  }
+
+  fn visit_semantic_span_mut(&mut self, span: &mut SemanticSpan<'a>) {
+    self.walk_semantic_span_mut(span)
+  }
+
+  fn visit_bracket_span_mut(&mut self, span: &mut BracketSpan<'a>) {
+    self.walk_bracket_span_mut(span)
+  }
+
+  fn visit_macro_span_mut(&mut self, span: &mut MacroSpan<'a>) {
+    self.walk_macro_span_mut(span)
+  }
+
+  fn visit_command_span_mut(&mut self, span: &mut CommandSpan<'a>) {
+    self.walk_command_span_mut(span)
+  }
+
+  fn visit_block_list_mut(&mut self, block_list: &mut [Block<'a>]) {
+    for block in block_list {
+      self.visit_block_mut(block);
+    }
+  }
+
+  fn walk_block_mut(&mut self, block: &mut Block<'a>) {
+    match block {
+      Block::Singleline(block) => self.visit_singleline_block_mut(block),
+      Block::Multiline(block) => self.visit_multiline_block_mut(block),
+    }
+  }
+
+  fn walk_singleline_block_mut(&mut self, block: &mut SinglelineBlock<'a>) {
+    match block {
+      SinglelineBlock::OpenHeading(span_list, _) | SinglelineBlock::ClosedHeading(span_list, _) => {
+        for span in span_list {
+          self.visit_span_mut(span);
+        }
+      }
+      SinglelineBlock::Comment(_) => {}
+    }
+  }
+
+  fn walk_multiline_block_mut(&mut self, block: &mut MultilineBlock<'a>) {
+    match block {
+      MultilineBlock::Blockquote(block_list) | MultilineBlock::Indent(block_list) => {
+        for block in block_list {
+          self.visit_multiline_block_mut(block);
+        }
+      }
+      MultilineBlock::OrderedList(item_list, _, _)
+      | MultilineBlock::UnorderedList(item_list, _) => {
+        for item in item_list {
+          self.visit_list_item_mut(item);
+        }
+      }
+      MultilineBlock::Paragraph(span_list) => {
+        for span in span_list {
+          self.visit_span_mut(span);
+        }
+      }
+      MultilineBlock::HorizontalRule => {}
+    }
+  }
+
+  fn walk_list_item_mut(&mut self, item: &mut ListItem<'a>) {
+    for block in item.0.iter_mut() {
+      self.visit_multiline_block_mut(block);
+    }
+  }
+
+  fn walk_span_mut(&mut self, span: &mut Span<'a>) {
+    match span {
+      Span::Semantic(span) => self.visit_semantic_span_mut(span),
+      Span::Bracket(span) => self.visit_bracket_span_mut(span),
+      Span::Macro(span) => self.visit_macro_span_mut(span),
+      Span::Command(span) => self.visit_command_span_mut(span),
+      Span::Inline(_) | Span::Escaped(_) => {}
+    }
+  }
+
+  fn walk_semantic_span_mut(&mut self, span: &mut SemanticSpan<'a>) {
+    match span {
+      SemanticSpan::Delete(span_list)
+      | SemanticSpan::Emphasis(span_list)
+      | SemanticSpan::Strong(span_list)
+      | SemanticSpan::Subscript(span_list)
+      | SemanticSpan::Superscript(span_list)
+      | SemanticSpan::Underline(span_list) => {
+        for span in span_list {
+          self.visit_span_mut(span);
+        }
+      }
+      SemanticSpan::Linebreak => {}
+    }
+  }
+
+  fn walk_bracket_span_mut(&mut self, span: &mut BracketSpan<'a>) {
+    match span {
+      BracketSpan::Color(span_list, _)
+      | BracketSpan::SizeDown(span_list, _)
+      | BracketSpan::SizeUp(span_list, _) => {
+        for span in span_list {
+          self.visit_span_mut(span);
+        }
+      }
+      BracketSpan::Folding(block_list) => {
+        for block in block_list {
+          self.visit_multiline_block_mut(block);
+        }
+      }
+      BracketSpan::Inline(_) | BracketSpan::SyntaxHighlight(_, _) => {}
+    }
+  }
+
+  fn walk_macro_span_mut(&mut self, span: &mut MacroSpan<'a>) {
+    if let MacroSpan::Comment(span_list, _) = span {
+      for span in span_list {
+        self.visit_span_mut(span);
+      }
+    }
+  }
+
+  fn walk_command_span_mut(&mut self, span: &mut CommandSpan<'a>) {
+    if let CommandSpan::Link(span_list, _, _) = span {
+      for span in span_list {
+        self.visit_span_mut(span);
+      }
+    }
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use crate::*;
+
+  #[test]
+  fn visit_in_document_order() {
+    struct InlineList<'a>(Vec<&'a str>);
+
+    impl<'a> Visitor<'a> for InlineList<'a> {
+      fn visit_span(&mut self, span: &'a Span<'a>) {
+        if let Span::Inline(inline) = span {
+          self.0.push(inline);
+        }
+        self.walk_span(span);
+      }
+    }
+
+    let source = "= a =\n * b\n  * '''c'''\n{{{#!folding 접기\n>[[d|e]]}}}\n[* f]";
+    let block_list = parse(source);
+    let mut visitor = InlineList(vec![]);
+    visitor.visit_block_list(&block_list);
+    assert_eq!(visitor.0, vec!["a", "b", "c", "접기", "e", "f"]);
+  }
+
+  #[test]
+  fn skip_children() {
+    struct SpanCount(usize);
+
+    impl<'a> Visitor<'a> for SpanCount {
+      fn visit_span(&mut self, span: &'a Span<'a>) {
+        self.0 += 1;
+        self.walk_span(span);
+      }
+
+      fn visit_command_span(&mut self, _: &'a CommandSpan<'a>) {}
+    }
+
+    let block_list = parse("[[a|'''b''']] ''c''");
+    let mut visitor = SpanCount(0);
+    visitor.visit_block_list(&block_list);
+    assert_eq!(visitor.0, 4);
+  }
+
+  #[test]
+  fn visit_mut() {
+    struct Uppercase;
+
+    impl<'a> VisitorMut<'a> for Uppercase {
+      fn visit_span_mut(&mut self, span: &mut Span<'a>) {
+        if let Span::Inline(inline) = span {
+          *inline = inline.to_uppercase().into();
+        }
+        self.walk_span_mut(span);
+      }
+    }
+
+    let mut block_list = parse("a\n * ~~b~~");
+    Uppercase.visit_block_list_mut(&mut block_list);
+    assert_eq!(block_list, parse("A\n * ~~B~~"));
+  }
+}